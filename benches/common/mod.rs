@@ -0,0 +1,16 @@
+//! Helpers shared by the benchmarks.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// Runs `f` for about a second and returns the fastest run.
+pub fn bench<T>(f: impl Fn() -> T) -> Duration {
+    let start = Instant::now();
+    let mut best = Duration::MAX;
+    while start.elapsed() < Duration::from_secs(1) {
+        let run = Instant::now();
+        black_box(f());
+        best = best.min(run.elapsed());
+    }
+    best
+}
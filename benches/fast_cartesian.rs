@@ -0,0 +1,158 @@
+//! Benchmarks the lending `MultiProduct` on the search of day 07, against the `unsafe` version it replaced, against
+//! `itertools`' allocating product and against the pruned search of `expr_search`.
+//!
+//! Run with `cargo bench --bench fast_cartesian`. The equations are generated from a fixed seed, so runs are comparable.
+
+mod common;
+
+use std::hint::black_box;
+
+use advent_of_code::expr_search::{self, evaluate, Op};
+use advent_of_code::fast_cartesian::IntoLendingExt;
+use advent_of_code::gen::{self, Rng};
+use gat_lending_iterator::LendingIterator;
+use itertools::Itertools;
+
+use common::bench;
+
+struct Equation {
+    answer: u64,
+    inputs: Vec<u64>,
+}
+
+fn equations() -> Vec<Equation> {
+    // about the size of a puzzle input.
+    gen::equations(&mut Rng::new(7), 850)
+        .items
+        .iter()
+        .map(|line| {
+            let (answer, inputs) = line.split_once(": ").unwrap();
+            Equation {
+                answer: answer.parse().unwrap(),
+                inputs: inputs.split(' ').map(|i| i.parse().unwrap()).collect(),
+            }
+        })
+        .collect()
+}
+
+fn eval(equation: &Equation, ops: &[Op]) -> bool {
+    evaluate(&equation.inputs, ops) == Some(equation.answer)
+}
+
+fn lending<const N: usize>(equations: &[Equation], ops: [Op; N]) -> usize {
+    equations
+        .iter()
+        .filter(|equation| {
+            let factors = equation.inputs[1..].iter().map(|_| ops);
+            IntoLendingExt::multi_cartesian_product(factors).any(|ops| eval(equation, ops))
+        })
+        .count()
+}
+
+/// `MultiProduct` before its `unsafe` was removed, which returned the current items from inside the loop of `next` by
+/// transmuting away their lifetime. Only kept here to compare the speed of both.
+struct Transmuted<I: Iterator + Clone> {
+    iters: Vec<(I, I)>,
+    cur: Option<Vec<I::Item>>,
+    ended: bool,
+}
+
+impl<I: Iterator + Clone> Transmuted<I> {
+    fn new(iters: impl Iterator<Item = I>) -> Self {
+        Transmuted {
+            iters: iters.map(|iter| (iter.clone(), iter)).collect(),
+            cur: None,
+            ended: false,
+        }
+    }
+
+    fn next(&mut self) -> Option<&[I::Item]> {
+        if self.ended {
+            return None;
+        }
+        match &mut self.cur {
+            Some(values) => {
+                for ((iter, iter_orig), item) in self.iters.iter_mut().zip(values.iter_mut()).rev()
+                {
+                    if let Some(new) = iter.next() {
+                        *item = new;
+                        // SAFETY: Polonius
+                        return Some(unsafe { core::mem::transmute::<&[_], &[_]>(values) });
+                    } else {
+                        *iter = iter_orig.clone();
+                        *item = iter.next().unwrap();
+                    }
+                }
+                self.ended = true;
+                None
+            }
+            None => {
+                let next: Option<Vec<_>> =
+                    self.iters.iter_mut().map(|(iter, _)| iter.next()).collect();
+                if next.is_none() || self.iters.is_empty() {
+                    self.ended = true;
+                    None
+                } else {
+                    self.cur = next;
+                    self.cur
+                        .as_deref()
+                        .map(|val| unsafe { core::mem::transmute::<&[_], &[_]>(val) })
+                }
+            }
+        }
+    }
+}
+
+fn transmuted<const N: usize>(equations: &[Equation], ops: [Op; N]) -> usize {
+    equations
+        .iter()
+        .filter(|equation| {
+            let mut product = Transmuted::new(equation.inputs[1..].iter().map(|_| ops.into_iter()));
+            while let Some(ops) = product.next() {
+                if eval(equation, ops) {
+                    return true;
+                }
+            }
+            false
+        })
+        .count()
+}
+
+fn allocating<const N: usize>(equations: &[Equation], ops: [Op; N]) -> usize {
+    equations
+        .iter()
+        .filter(|equation| {
+            let factors = equation.inputs[1..].iter().map(|_| ops);
+            Itertools::multi_cartesian_product(factors).any(|ops| eval(equation, &ops))
+        })
+        .count()
+}
+
+fn pruned<const N: usize>(equations: &[Equation], ops: [Op; N]) -> usize {
+    equations
+        .iter()
+        .filter(|equation| expr_search::solvable(equation.answer, &equation.inputs, &ops))
+        .count()
+}
+
+fn compare<const N: usize>(name: &str, equations: &[Equation], ops: [Op; N]) {
+    let expected = lending(equations, ops);
+    assert_eq!(transmuted(equations, ops), expected);
+    assert_eq!(allocating(equations, ops), expected);
+    assert_eq!(pruned(equations, ops), expected);
+
+    let lending = bench(|| lending(black_box(equations), ops));
+    let transmuted = bench(|| transmuted(black_box(equations), ops));
+    let allocating = bench(|| allocating(black_box(equations), ops));
+    let pruned = bench(|| pruned(black_box(equations), ops));
+    println!(
+        "{name:<12} fast_cartesian: {lending:>10.1?}   transmute: {transmuted:>10.1?}   itertools: {allocating:>10.1?}   \
+        expr_search: {pruned:>10.1?}"
+    );
+}
+
+fn main() {
+    let equations = equations();
+    compare("try_add_mul", &equations, [Op::Add, Op::Mul]);
+    compare("try_any", &equations, [Op::Add, Op::Mul, Op::Concat]);
+}
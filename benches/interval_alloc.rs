@@ -0,0 +1,89 @@
+//! Benchmarks `IntervalAllocator` on day 09's file compaction, against scanning the free ranges for each file like the
+//! solution used to.
+//!
+//! Run with `cargo bench --bench interval_alloc`. The disk maps are generated from a fixed seed, so runs are comparable.
+
+mod common;
+
+use std::hint::black_box;
+use std::ops::Range;
+
+use advent_of_code::gen::{self, Rng};
+use advent_of_code::interval_alloc::IntervalAllocator;
+
+use common::bench;
+
+struct Disk {
+    files: Vec<Range<usize>>,
+    gaps: Vec<Range<usize>>,
+}
+
+fn disk(size: usize) -> Disk {
+    let map = gen::disk_map(&mut Rng::new(9), size).render();
+    let mut disk = Disk {
+        files: Vec::new(),
+        gaps: Vec::new(),
+    };
+    let mut pos = 0;
+    for (i, len) in map.trim().bytes().map(|c| (c - b'0') as usize).enumerate() {
+        let range = pos..pos + len;
+        if i % 2 == 0 {
+            disk.files.push(range);
+        } else if !range.is_empty() {
+            disk.gaps.push(range);
+        }
+        pos += len;
+    }
+    disk
+}
+
+/// Where each file ends up, moving files from the right into the leftmost gap that fits.
+fn scan(disk: &Disk) -> Vec<usize> {
+    let mut gaps = disk.gaps.clone();
+    let mut moved = disk.files.iter().map(|file| file.start).collect::<Vec<_>>();
+    for (file, start) in disk.files.iter().zip(&mut moved).rev() {
+        if let Some(gap) = gaps
+            .iter_mut()
+            .find(|gap| gap.len() >= file.len() && gap.start < file.start)
+        {
+            *start = gap.start;
+            gap.start += file.len();
+        }
+    }
+    moved
+}
+
+fn allocator(disk: &Disk) -> Vec<usize> {
+    let mut free = IntervalAllocator::new();
+    for gap in &disk.gaps {
+        free.free(gap.clone());
+    }
+    let mut moved = disk.files.iter().map(|file| file.start).collect::<Vec<_>>();
+    for (file, start) in disk.files.iter().zip(&mut moved).rev() {
+        let Some(gap) = free
+            .leftmost_fit(file.len())
+            .filter(|gap| gap.start < file.start)
+        else {
+            continue;
+        };
+        *start = gap.start;
+        free.allocate(gap.start..gap.start + file.len());
+        free.free(file.clone());
+    }
+    moved
+}
+
+fn compare(size: usize) {
+    let disk = disk(size);
+    assert_eq!(allocator(&disk), scan(&disk));
+
+    let scan = bench(|| scan(black_box(&disk)));
+    let allocator = bench(|| allocator(black_box(&disk)));
+    println!("{size:>6} files  scan: {scan:>10.1?}   interval_alloc: {allocator:>10.1?}");
+}
+
+fn main() {
+    for size in [1_000, 10_000, 50_000] {
+        compare(size);
+    }
+}
@@ -1,3 +1,4 @@
+use advent_of_code::Direction8;
 use itertools::Itertools;
 
 advent_of_code::solution!(4);
@@ -29,14 +30,10 @@ fn check_xmas(matrix: &Vec<Vec<char>>, x: usize, y: usize) -> u32 {
         return 0;
     }
     let mut out = 0;
-    for x_diff in -1..=1 {
-        for y_diff in -1..=1 {
-            if x_diff == 0 && y_diff == 0 {
-                continue;
-            }
-            if check_line(matrix, x, y, x_diff, y_diff).is_some() {
-                out += 1;
-            }
+    for dir in Direction8::all() {
+        let unit = dir.unit();
+        if check_line(matrix, x, y, *unit.x(), *unit.y()).is_some() {
+            out += 1;
         }
     }
     out
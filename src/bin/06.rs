@@ -1,17 +1,22 @@
+use advent_of_code::bounds::Rect;
+use advent_of_code::grid::{DirectedGridSet, GridSet};
+use advent_of_code::viz::{self, Canvas, Cell, Class};
 use advent_of_code::Cardinal;
 use numeric::compound::vector::Vec2;
-use std::collections::HashSet;
 
 advent_of_code::solution!(6);
 
-#[derive(Default)]
 struct Map {
-    blocks: HashSet<Vec2<u32>>,
-    size: Vec2<u32>,
+    blocks: GridSet,
+    bounds: Rect<u32>,
     guard: Vec2<u32>,
 }
 
 impl Map {
+    fn grid_size(&self) -> Vec2<usize> {
+        self.blocks.size()
+    }
+
     fn start_state(&self) -> GuardState {
         GuardState {
             pos: self.guard,
@@ -41,90 +46,52 @@ enum MoveFail {
 
 impl Map {
     fn from_input(input: &str) -> Map {
-        let mut map = Map::default();
+        let mut guard = Vec2::default();
+        let mut blocks = Vec::new();
 
-        let mut size = Vec2::default();
+        let mut bounds = Rect::new(Vec2::default(), Vec2::default());
         for (y, line) in input.lines().rev().enumerate() {
             let y = y as u32;
 
-            if y > *size.y() {
-                size.set_y(y);
-            }
             for (x, char) in line.chars().enumerate() {
                 let x = x as u32;
 
-                if x > *size.x() {
-                    size.set_x(x);
-                }
+                bounds.include(Vec2::new([x, y]));
                 if char == '^' {
-                    map.guard = Vec2::new([x, y]);
+                    guard = Vec2::new([x, y]);
                 } else if char == '#' {
-                    map.blocks.insert(Vec2::new([x, y]));
+                    blocks.push(Vec2::new([x, y]));
                 }
             }
         }
-        map.size = size + 1;
 
-        map
+        let mut grid = GridSet::new(bounds.size());
+        for block in blocks {
+            grid.insert(block);
+        }
+        Map {
+            blocks: grid,
+            bounds,
+            guard,
+        }
     }
 
-    #[allow(unused)]
-    fn print(&self) {
-        for y in (0..=*self.size.y()).rev() {
-            for x in 0..=*self.size.x() {
-                if self.guard == Vec2::new([x, y]) {
-                    print!("^");
-                } else if self.blocks.contains(&(Vec2::new([x, y]))) {
-                    print!("#")
-                } else {
-                    print!(".")
-                }
-            }
-            println!();
+    fn draw(&self) -> Canvas {
+        let mut canvas = Canvas::y_up(self.grid_size());
+        for block in self.blocks.iter() {
+            canvas.set(block, Cell::new('#', Class::Wall));
         }
+        canvas.set(self.guard, Cell::new('^', Class::Agent));
+        canvas
     }
 
-    #[allow(unused)]
-    fn print_with_paths(&self, block: Vec2<u32>, locs: &HashSet<(Vec2<u32>, Cardinal)>) {
-        for y in (0..=*self.size.y()).rev() {
-            for x in 0..=*self.size.x() {
-                if Vec2::new([x, y]) == block {
-                    print!("O");
-                    continue;
-                }
-
-                let up = locs.contains(&(Vec2::new([x, y]), Cardinal::Up));
-                let down = locs.contains(&(Vec2::new([x, y]), Cardinal::Down));
-                let left = locs.contains(&(Vec2::new([x, y]), Cardinal::Left));
-                let right = locs.contains(&(Vec2::new([x, y]), Cardinal::Right));
-                match (up, down, left, right) {
-                    (true, true, true, true) => print!("┼"),
-                    (true, true, true, false) => print!("├"),
-                    (true, true, false, true) => print!("┥"),
-                    (true, true, false, false) => print!("│"),
-                    (true, false, true, true) => print!("┬"),
-                    (true, false, true, false) => print!("└"),
-                    (true, false, false, true) => print!("┌"),
-                    (true, false, false, false) => print!("│"),
-                    (false, true, true, true) => print!("┴"),
-                    (false, true, true, false) => print!("┘"),
-                    (false, true, false, true) => print!("┐"),
-                    (false, true, false, false) => print!("│"),
-                    (false, false, true, true) => print!("─"),
-                    (false, false, true, false) => print!("─"),
-                    (false, false, false, true) => print!("─"),
-                    (false, false, false, false) => {
-                        if self.blocks.contains(&(Vec2::new([x, y]))) {
-                            print!("#")
-                        } else {
-                            print!(".")
-                        }
-                    }
-                }
-            }
-            println!()
+    fn draw_paths(&self, block: Vec2<u32>, locs: &DirectedGridSet) -> Canvas {
+        let mut canvas = self.draw();
+        for pos in locs.cells().iter() {
+            canvas.path(pos, locs.directions(pos));
         }
-        println!();
+        canvas.set(block, Cell::new('O', Class::Highlight));
+        canvas
     }
 
     fn try_move(&self, state: &GuardState) -> Result<Vec2<u32>, MoveFail> {
@@ -132,9 +99,9 @@ impl Map {
             .direction
             .try_move(state.pos)
             .ok_or(MoveFail::OffMap)?;
-        if next.x() >= self.size.x() || next.y() >= self.size.y() {
+        if !self.bounds.contains(next) {
             Err(MoveFail::OffMap)
-        } else if self.blocks.contains(&next)
+        } else if self.blocks.contains(next)
             || state.extra_block.is_some_and(|block| next == block)
         {
             Err(MoveFail::Blocked)
@@ -147,7 +114,7 @@ impl Map {
 pub fn part_one(input: &str) -> Option<u32> {
     let map = Map::from_input(input);
 
-    let mut visited = HashSet::new();
+    let mut visited = GridSet::new(map.grid_size());
     let mut guard = map.start_state();
     loop {
         visited.insert(guard.pos);
@@ -157,19 +124,30 @@ pub fn part_one(input: &str) -> Option<u32> {
             Err(MoveFail::OffMap) => break,
         }
     }
+    viz::frame("Visited", || {
+        let mut canvas = map.draw();
+        for pos in visited.iter() {
+            canvas.set(pos, Cell::new('X', Class::Path));
+        }
+        canvas
+    });
     Some(visited.len() as u32)
 }
 
-fn check_loop(map: &Map, mut guard: GuardState) -> bool {
-    let mut seen_locs = HashSet::new();
+/// Whether the guard walks in a loop. `seen_locs` is cleared first, so one set can be reused for every candidate.
+fn check_loop(map: &Map, mut guard: GuardState, seen_locs: &mut DirectedGridSet) -> bool {
+    seen_locs.clear();
     guard.rotate_right();
     loop {
         // Can't shortcut by turn count - some loops may be more complex than that
-        if seen_locs.contains(&guard) {
-            // map.print_with_paths(_block, &seen_locs);
+        if !seen_locs.insert(guard.pos, guard.direction) {
+            if let Some(block) = guard.extra_block {
+                viz::frame(format!("Loop with a block at {},{}", block.x(), block.y()), || {
+                    map.draw_paths(block, seen_locs)
+                });
+            }
             return true;
         }
-        seen_locs.insert(guard.clone());
 
         match map.try_move(&guard) {
             Ok(next) => guard.pos = next,
@@ -182,7 +160,8 @@ fn check_loop(map: &Map, mut guard: GuardState) -> bool {
 pub fn part_two(input: &str) -> Option<u32> {
     let map = Map::from_input(input);
 
-    let mut seen_locs = HashSet::new();
+    let mut seen_locs = GridSet::new(map.grid_size());
+    let mut loop_locs = DirectedGridSet::new(map.grid_size());
     let mut guard = map.start_state();
     let mut loops = 0;
     loop {
@@ -191,9 +170,9 @@ pub fn part_two(input: &str) -> Option<u32> {
         match map.try_move(&guard) {
             Ok(next) => {
                 // Try inserting next as block
-                if !seen_locs.contains(&next) {
+                if !seen_locs.contains(next) {
                     guard.extra_block = Some(next);
-                    if check_loop(&map, guard.clone()) {
+                    if check_loop(&map, guard.clone(), &mut loop_locs) {
                         loops += 1;
                     }
                     guard.extra_block = None;
@@ -227,7 +206,8 @@ mod tests {
         );
         let mut guard = map.start_state();
         guard.extra_block = Some(Vec2::new([5, 2]));
-        assert!(check_loop(&map, map.start_state()));
+        let mut seen_locs = DirectedGridSet::new(map.grid_size());
+        assert!(check_loop(&map, map.start_state(), &mut seen_locs));
 
         let map = Map::from_input(
             "\
@@ -237,7 +217,8 @@ mod tests {
         );
         let mut guard = map.start_state();
         guard.extra_block = Some(Vec2::new([2, 2]));
-        assert!(check_loop(&map, map.start_state()));
+        let mut seen_locs = DirectedGridSet::new(map.grid_size());
+        assert!(check_loop(&map, map.start_state(), &mut seen_locs));
     }
 
     #[test]
@@ -1,16 +1,9 @@
-use advent_of_code::{fast_cartesian::IntoLendingExt, int_u64, Parser};
+use advent_of_code::expr_search::{solvable, Op};
+use advent_of_code::{int_u64, Parser};
 use chumsky::prelude::*;
-use gat_lending_iterator::LendingIterator;
 
 advent_of_code::solution!(7);
 
-#[derive(Copy, Clone, Debug)]
-enum Op {
-    Add,
-    Mul,
-    Concat,
-}
-
 struct Equation {
     answer: u64,
     inputs: Vec<u64>,
@@ -18,39 +11,11 @@ struct Equation {
 
 impl Equation {
     fn try_add_mul(&self) -> bool {
-        self.inputs[1..]
-            .iter()
-            .map(|_| [Op::Add, Op::Mul])
-            .multi_cartesian_product()
-            .any(|ops| {
-                let val =
-                    ops.iter()
-                        .zip(&self.inputs[1..])
-                        .fold(self.inputs[0], |acc, (&op, &val)| match op {
-                            Op::Add => acc + val,
-                            Op::Mul => acc * val,
-                            _ => unreachable!(),
-                        });
-                val == self.answer
-            })
+        solvable(self.answer, &self.inputs, &[Op::Add, Op::Mul])
     }
 
     fn try_any(&self) -> bool {
-        self.inputs[1..]
-            .iter()
-            .map(|_| [Op::Add, Op::Mul, Op::Concat])
-            .multi_cartesian_product()
-            .any(|ops| {
-                let val =
-                    ops.iter()
-                        .zip(&self.inputs[1..])
-                        .fold(self.inputs[0], |acc, (&op, &val)| match op {
-                            Op::Add => acc + val,
-                            Op::Mul => acc * val,
-                            Op::Concat => acc * 10u64.pow(val.ilog10() + 1) + val,
-                        });
-                val == self.answer
-            })
+        solvable(self.answer, &self.inputs, &[Op::Add, Op::Mul, Op::Concat])
     }
 }
 
@@ -1,3 +1,5 @@
+use advent_of_code::bounds::Rect;
+use advent_of_code::viz::{self, Canvas, Cell, Class};
 use chumsky::text::Char;
 use itertools::Itertools;
 use numeric::compound::vector::Vec2;
@@ -8,25 +10,20 @@ advent_of_code::solution!(8);
 struct Map {
     // Map of frequencies to sets of antennas
     antennas: BTreeMap<u8, Vec<Vec2<i32>>>,
-    size: Vec2<i32>,
+    bounds: Rect<i32>,
 }
 
 impl Map {
     fn from_input(input: &str) -> Map {
         let mut antennas = BTreeMap::<_, Vec<_>>::new();
-        let mut size = Vec2::default();
+        let mut bounds = Rect::new(Vec2::default(), Vec2::default());
         for (y, line) in input.lines().rev().enumerate() {
             let y = y as i32;
 
-            if y > *size.y() {
-                size.set_y(y);
-            }
             for (x, char) in line.chars().enumerate() {
                 let x = x as i32;
 
-                if x > *size.x() {
-                    size.set_x(x);
-                }
+                bounds.include(Vec2::new([x, y]));
                 if char != '.' {
                     antennas
                         .entry(char as u8)
@@ -35,36 +32,20 @@ impl Map {
                 }
             }
         }
-        Map { antennas, size }
+        Map { antennas, bounds }
     }
 
-    #[allow(unused)]
-    fn print(&self, nodes: &HashSet<Vec2<i32>>) {
-        for y in (0..=*self.size.y()).rev() {
-            for x in 0..=*self.size.x() {
-                let loc = Vec2::new([x, y]);
-                if let Some((&freq, _)) = self
-                    .antennas
-                    .iter()
-                    .find(|(freq, locs)| locs.contains(&loc))
-                {
-                    print!("{}", char::from_ascii(freq))
-                } else if nodes.contains(&loc) {
-                    print!("#");
-                } else {
-                    print!(".");
-                }
+    fn draw(&self, nodes: &HashSet<Vec2<i32>>) -> Canvas {
+        let mut canvas = Canvas::y_up(self.bounds.size());
+        for &node in nodes {
+            canvas.set(node, Cell::new('#', Class::Highlight));
+        }
+        for (&freq, locs) in &self.antennas {
+            for &loc in locs {
+                canvas.set(loc, Cell::new(freq as char, Class::Object));
             }
-            println!()
         }
-    }
-}
-
-fn validate_node(node: Vec2<i32>, size: Vec2<i32>) -> Option<Vec2<i32>> {
-    if (0..=*size.x()).contains(node.x()) && (0..=*size.y()).contains(node.y()) {
-        Some(node)
-    } else {
-        None
+        canvas
     }
 }
 
@@ -84,7 +65,7 @@ fn find_antinodes(map: &Map) -> HashSet<Vec2<i32>> {
                 // Get vector pointing from b to a: (b - a)
                 // Double it, re-add it to a
                 let node = a + (b - a) * 2;
-                validate_node(node, map.size)
+                map.bounds.contains(node).then_some(node)
             })
             .filter_map(|v| v);
         positions.extend(new_pos);
@@ -109,10 +90,10 @@ fn find_more_antinodes(map: &Map) -> HashSet<Vec2<i32>> {
                 // Double it, re-add it to a
                 let diff = b - a;
                 let mut out = Vec::new();
-                let mut factor = 1;
-                while let Some(node) = validate_node(a + diff * factor, map.size) {
-                    factor += 1;
+                let mut node = a + diff;
+                while map.bounds.contains(node) {
                     out.push(node);
+                    node = node + diff;
                 }
                 out
             })
@@ -126,12 +107,14 @@ fn find_more_antinodes(map: &Map) -> HashSet<Vec2<i32>> {
 pub fn part_one(input: &str) -> Option<u32> {
     let map = Map::from_input(input);
     let out = find_antinodes(&map);
+    viz::frame("Antinodes", || map.draw(&out));
     Some(out.len() as u32)
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
     let map = Map::from_input(input);
     let out = find_more_antinodes(&map);
+    viz::frame("Antinodes", || map.draw(&out));
     Some(out.len() as u32)
 }
 
@@ -1,3 +1,6 @@
+use advent_of_code::interval_alloc::IntervalAllocator;
+use advent_of_code::viz::{self, Canvas, Cell, Class};
+use numeric::compound::vector::Vec2;
 use std::iter;
 use std::iter::from_fn;
 use std::ops::Range;
@@ -85,22 +88,21 @@ impl Disk {
 
     fn compress_files(&mut self) {
         let files = self.file_ranges().collect::<Vec<_>>();
-        let mut empties = self.empty_ranges().collect::<Vec<_>>();
-        for file in files.into_iter().rev() {
-            self.try_compact_file(file.0, file.1, &mut empties);
+        let mut free = IntervalAllocator::new();
+        for range in self.empty_ranges() {
+            free.free(range);
+        }
+        for (idx, file) in files.into_iter().rev() {
+            // Files only move left, so if the leftmost free range that fits isn't left of the file, none is
+            let Some(slot) = free.leftmost_fit(file.len()).filter(|slot| slot.start < file.start) else {
+                continue;
+            };
+            let moved = slot.start..slot.start + file.len();
+            free.allocate(moved.clone());
+            free.free(file.clone());
+            self.data[file].fill(None);
+            self.data[moved].fill(Some(idx));
         }
-    }
-
-    fn try_compact_file(&mut self, idx: u64, file: Range<usize>, ranges: &mut [Range<usize>]) {
-        let Some(slot) = ranges
-            .iter_mut()
-            .find(|r| file.len() <= r.len() && file.start > r.start)
-        else {
-            return;
-        };
-        self.data[file.clone()].fill(None);
-        self.data[slot.start..slot.start + file.len()].fill(Some(idx));
-        slot.start = slot.start + file.len();
     }
 
     fn checksum(&self) -> u64 {
@@ -113,8 +115,7 @@ impl Disk {
             })
     }
 
-    #[allow(unused)]
-    fn print(&self) {
+    fn draw(&self) -> Canvas {
         fn encode_char(val: u64) -> char {
             const CHARS: [char; 62] = {
                 let mut out = ['\0'; 10 + 26 + 26];
@@ -142,29 +143,26 @@ impl Disk {
                 .unwrap_or_else(|| (255 as char..).nth(val as usize).unwrap())
         }
 
-        for &val in &self.data {
-            match val {
-                None => print!("."),
-                Some(val) => print!("{}", encode_char(val)),
-            }
-        }
-        println!()
+        Canvas::from_fn(Vec2::from_xy(self.data.len(), 1), |pos| match self.data[*pos.x()] {
+            None => Cell::EMPTY,
+            Some(val) => Cell::new(encode_char(val), Class::Object),
+        })
     }
 }
 
 pub fn part_one(input: &str) -> Option<u64> {
     let mut disk = Disk::from_input(input);
-    // disk.print();
+    viz::frame("Before", || disk.draw());
     disk.compress_bytes();
-    // disk.print();
+    viz::frame("After", || disk.draw());
     Some(disk.checksum())
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
     let mut disk = Disk::from_input(input);
-    // disk.print();
+    viz::frame("Before", || disk.draw());
     disk.compress_files();
-    // disk.print();
+    viz::frame("After", || disk.draw());
     Some(disk.checksum())
 }
 
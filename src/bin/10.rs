@@ -1,4 +1,5 @@
-use advent_of_code::Cardinal;
+use advent_of_code::bounds::Rect;
+use advent_of_code::Neighbours;
 use numeric::compound::vector::Vec2;
 use std::collections::{HashMap, HashSet};
 
@@ -6,7 +7,7 @@ advent_of_code::solution!(10);
 
 struct Map {
     topography: Vec<Vec<u8>>,
-    size: Vec2<usize>,
+    bounds: Rect<usize>,
 }
 
 impl Map {
@@ -20,7 +21,7 @@ impl Map {
         let x = topography.iter().map(|l| l.len()).max().unwrap();
         Map {
             topography,
-            size: Vec2::new([x, y]),
+            bounds: Rect::from_size(Vec2::new([x, y])).expect("the map is empty"),
         }
     }
 
@@ -39,15 +40,6 @@ impl Map {
     fn height(&self, pos: Vec2<usize>) -> u8 {
         self.topography[*pos.y()][*pos.x()]
     }
-
-    fn try_move(&self, dir: Cardinal, pos: Vec2<usize>) -> Option<Vec2<usize>> {
-        let next = dir.try_move(pos)?;
-        if next.x() < self.size.x() && next.y() < self.size.y() {
-            Some(next)
-        } else {
-            None
-        }
-    }
 }
 
 fn find_ends(map: &Map, start: Vec2<usize>) -> Vec<Vec2<usize>> {
@@ -59,10 +51,7 @@ fn find_ends(map: &Map, start: Vec2<usize>) -> Vec<Vec2<usize>> {
             return;
         }
 
-        for dir in Cardinal::all() {
-            let Some(pos) = map.try_move(dir, pos) else {
-                continue;
-            };
+        for pos in pos.neighbours4().filter(|&pos| map.bounds.contains(pos)) {
             if map.height(pos) == height - 1 {
                 search(map, pos, height - 1, out)
             }
@@ -1,3 +1,4 @@
+use advent_of_code::bounds::Rect;
 use advent_of_code::{Cardinal, Diagonal};
 use numeric::compound::vector::Vec2;
 use std::collections::HashSet;
@@ -6,7 +7,7 @@ advent_of_code::solution!(12);
 
 struct Map {
     data: Vec<Vec<u8>>,
-    size: Vec2<usize>,
+    bounds: Rect<usize>,
 }
 
 impl Map {
@@ -20,7 +21,7 @@ impl Map {
         let x = data.iter().map(|l| l.len()).max().unwrap();
         Map {
             data,
-            size: Vec2::new([x, y]),
+            bounds: Rect::from_size(Vec2::new([x, y])).expect("the map is empty"),
         }
     }
 
@@ -33,21 +34,11 @@ impl Map {
     }
 
     fn try_move(&self, pos: Vec2<usize>, dir: Cardinal) -> Option<Vec2<usize>> {
-        let pos = dir.try_move(pos)?;
-        if pos.y() < self.size.y() && pos.x() < self.size.x() {
-            Some(pos)
-        } else {
-            None
-        }
+        dir.try_move(pos).filter(|&pos| self.bounds.contains(pos))
     }
 
     fn try_move_diag(&self, pos: Vec2<usize>, diag: Diagonal) -> Option<Vec2<usize>> {
-        let pos = diag.try_move(pos)?;
-        if pos.y() < self.size.y() && pos.x() < self.size.x() {
-            Some(pos)
-        } else {
-            None
-        }
+        diag.try_move(pos).filter(|&pos| self.bounds.contains(pos))
     }
 
     fn eat_region(&mut self, pos: Vec2<usize>) -> Option<Region> {
@@ -184,8 +175,9 @@ struct SideRegion {
 pub fn part_one(input: &str) -> Option<u64> {
     let mut map = Map::from_input(input);
     let mut regions = Vec::new();
-    for x in 0..*map.size.x() {
-        for y in 0..*map.size.y() {
+    let size = map.bounds.size();
+    for x in 0..*size.x() {
+        for y in 0..*size.y() {
             if let Some(r) = map.eat_region(Vec2::new([x, y])) {
                 regions.push(r);
             }
@@ -199,8 +191,9 @@ pub fn part_two(input: &str) -> Option<u64> {
     let map = Map::from_input(input);
     let mut regions = Vec::new();
     let mut global_seen = HashSet::new();
-    for x in 0..*map.size.x() {
-        for y in 0..*map.size.y() {
+    let size = map.bounds.size();
+    for x in 0..*size.x() {
+        for y in 0..*size.y() {
             let pos = Vec2::new([x, y]);
             if let Some(r) = map.eat_side_region(pos, &mut global_seen) {
                 regions.push(r);
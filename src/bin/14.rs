@@ -1,13 +1,31 @@
-use std::cmp::Ordering;
-use std::sync::Mutex;
 use chumsky::Parser;
-use numeric::compound::matrix::DynMatrix;
 use numeric::compound::vector::Vec2;
-use advent_of_code::{int_i64, Parser};
+use advent_of_code::{detect, int_i64, Parser};
+use advent_of_code::grid::GridSet;
+use advent_of_code::template::{Context, Params};
+use advent_of_code::viz::{self, Canvas, Cell, Class};
 
-pub static GRID_SIZE: Mutex<Vec2<i64>> = Mutex::new(Vec2::new([101, 103]));
+advent_of_code::solution!(14, ctx: Grid);
 
-advent_of_code::solution!(14);
+/// The area the robots move in. The examples use a smaller grid, set in `14.params`.
+pub struct Grid {
+    size: Vec2<i64>,
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Grid { size: Vec2::new([101, 103]) }
+    }
+}
+
+impl Context for Grid {
+    fn from_params(params: &Params) -> Result<Self, String> {
+        let default = Grid::default().size;
+        let width = params.int("width")?.unwrap_or(*default.x());
+        let height = params.int("height")?.unwrap_or(*default.y());
+        Ok(Grid { size: Vec2::from_xy(width, height) })
+    }
+}
 
 #[derive(Debug)]
 struct Robot {
@@ -25,10 +43,6 @@ impl Robot {
     }
 }
 
-fn grid_size() -> Vec2<i64> {
-    *GRID_SIZE.lock().unwrap()
-}
-
 fn parser<'a>() -> Parser!['a, Vec<Robot>] {
     use chumsky::prelude::*;
 
@@ -45,81 +59,75 @@ fn parser<'a>() -> Parser!['a, Vec<Robot>] {
         .collect::<Vec<_>>()
 }
 
-pub fn part_one(input: &str) -> Option<u64> {
-    let mut robots = parser().parse(input).unwrap();
-    let grid_size = grid_size();
-    for robot in &mut robots {
-        robot.pos = robot.pos_after(100, grid_size);
+pub fn part_one(input: &str, grid: &Grid) -> Option<u64> {
+    let robots = parser().parse(input).unwrap();
+    let grid_size = grid.size;
+    let mut recording = viz::Recording::new(DAY, "part_one.gif");
+    for time in 0..=100 {
+        recording.frame(|| draw(robots.iter().map(|robot| robot.pos_after(time, grid_size)), grid_size));
     }
+    recording.finish();
 
-    let mut quadrants = [0; 4];
-    for robot in robots {
-        let lines = grid_size / 2;
-        let x = robot.pos.x().cmp(lines.x());
-        let y = robot.pos.y().cmp(lines.y());
-        match (x, y) {
-            (Ordering::Less, Ordering::Less) => quadrants[0] += 1,
-            (Ordering::Less, Ordering::Greater) => quadrants[1] += 1,
-            (Ordering::Greater, Ordering::Less) => quadrants[2] += 1,
-            (Ordering::Greater, Ordering::Greater) => quadrants[3] += 1,
-            _ => (),
-        }
-    }
-    Some(quadrants.into_iter().product())
+    Some(detect::safety_factor(robots.iter().map(|robot| robot.pos_after(100, grid_size)), grid_size))
 }
 
-fn check_tree(robots: &[Robot], grid_size: Vec2<i64>) -> bool {
-    let mut grid = DynMatrix::new(vec![0; *grid_size.x() as usize * *grid_size.y() as usize], *grid_size.x() as usize, *grid_size.y() as usize);
-    for robot in robots {
-        grid[(*robot.pos.x() as usize, *robot.pos.y() as usize)] += 1;
-    }
-
-    let mut has_row = false;
-    for y in 0..(*grid_size.y() as usize) {
-        let mut row_count = 0;
-        for x in 0..(*grid_size.x() as usize) {
-            if grid[(x, y)] > 0 {
-                row_count += 1;
-            } else if grid[(x, y)] == 0 {
-                row_count = 0;
-            }
-            if row_count > 10 {
-                has_row = true;
-                break;
-            }
-        }
-    }
-
-    if has_row {
-        for y in 0..(*grid_size.y() as usize) {
-            for x in 0..(*grid_size.x() as usize) {
-                if grid[(x, y)] > 0 {
-                    print!("X")
-                } else {
-                    print!(".")
-                }
-            }
-            println!()
-        }
+fn draw(positions: impl IntoIterator<Item = Vec2<i64>>, grid_size: Vec2<i64>) -> Canvas {
+    let mut canvas = Canvas::new(Vec2::from_xy(*grid_size.x() as usize, *grid_size.y() as usize));
+    for pos in positions {
+        canvas.set(pos, Cell::new('X', Class::Agent));
     }
+    canvas
+}
 
-    has_row
+/// Pairs of steps, ordered by the sum of their positions in `xs` and `ys`.
+fn by_rank<'a>(xs: &'a [u64], ys: &'a [u64]) -> impl Iterator<Item = (u64, u64)> + 'a {
+    (0..xs.len() + ys.len()).flat_map(move |sum| {
+        (0..=sum).filter_map(move |i| Some((*xs.get(i)?, *ys.get(sum - i)?)))
+    })
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
-    let mut robots = parser().parse(input).unwrap();
-    let grid_size = grid_size();
+/// The step in `1..=width * height` where the x coordinates are as after `x_time` and the y coordinates as after
+/// `y_time`, if there is one.
+fn combine(x_time: i64, y_time: i64, grid_size: Vec2<i64>) -> Option<i64> {
+    let (width, height) = (*grid_size.x(), *grid_size.y());
+    let time = (0..height)
+        .map(|i| x_time + i * width)
+        .find(|time| time % height == y_time)?;
+    // Positions repeat after `width * height` steps, and the search starts at 1
+    Some(if time == 0 { width * height } else { time })
+}
 
-    for i in 1.. {
-        for robot in &mut robots {
-            robot.pos = robot.pos_after(1, grid_size);
-        }
+/// The fewest connected robots the picture is drawn with. Noise almost never connects this many.
+const MIN_PICTURE: usize = 50;
 
-        if check_tree(&robots, grid_size) {
-            return Some(i);
-        }
+pub fn part_two(input: &str, grid: &Grid) -> Option<u64> {
+    let robots = parser().parse(input).unwrap();
+    if robots.is_empty() {
+        return None;
     }
-    None
+    let grid_size = grid.size;
+    let (width, height) = (*grid_size.x(), *grid_size.y());
+    let positions = |time: i64| robots.iter().map(move |robot| robot.pos_after(time, grid_size));
+
+    // The x coordinates repeat every `width` steps and the y coordinates every `height` steps, and the picture gathers
+    // the robots on both axes. Try the steps where both axes are least spread first, until one has a picture.
+    let spread = |time: u64| detect::variance(positions(time as i64)).unwrap();
+    let x_times = detect::ranked(0..width as u64, |time| *spread(time).x());
+    let y_times = detect::ranked(0..height as u64, |time| *spread(time).y());
+    let mut cells = GridSet::new(Vec2::from_xy(width as usize, height as usize));
+    let time = by_rank(&x_times, &y_times)
+        .filter_map(|(x_time, y_time)| combine(x_time as i64, y_time as i64, grid_size))
+        .find(|&time| {
+            cells.clear();
+            for pos in positions(time) {
+                cells.insert(pos);
+            }
+            detect::largest_component(&cells) >= MIN_PICTURE
+        })?;
+
+    viz::frame(format!("After {time} seconds"), || draw(positions(time), grid_size));
+    viz::save(DAY, "tree.png", || draw(positions(time), grid_size));
+    Some(time as u64)
 }
 
 #[cfg(test)]
@@ -128,15 +136,19 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        *GRID_SIZE.lock().unwrap() = Vec2::new([11, 7]);
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_one(
+            &advent_of_code::template::read_file("examples", DAY),
+            &advent_of_code::template::read_context("examples", DAY),
+        );
         assert_eq!(result, Some(12));
     }
 
     #[test]
     fn test_part_two() {
-        *GRID_SIZE.lock().unwrap() = Vec2::new([11, 7]);
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_two(
+            &advent_of_code::template::read_file("examples", DAY),
+            &advent_of_code::template::read_context("examples", DAY),
+        );
         assert_eq!(result, None);
     }
 }
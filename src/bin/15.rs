@@ -3,7 +3,7 @@ use std::fmt::Write;
 use chumsky::Parser;
 use numeric::compound::matrix::DynMatrix;
 use numeric::compound::vector::Vec2;
-use advent_of_code::{Parser, Cardinal, VecExt};
+use advent_of_code::{arrow, Parser, Cardinal, VecExt};
 
 advent_of_code::solution!(15);
 
@@ -111,14 +111,7 @@ impl Warehouse {
 fn parser<'a>() -> Parser!['a, (Warehouse, Vec<Cardinal>)] {
     use chumsky::prelude::*;
 
-    let instrs = one_of("^v<>")
-        .map(|c: char| match c {
-            '^' => Cardinal::Up,
-            'v' => Cardinal::Down,
-            '<' => Cardinal::Left,
-            '>' => Cardinal::Right,
-            _ => unreachable!(),
-        })
+    let instrs = arrow()
         .repeated()
         .at_least(1)
         .collect::<Vec<_>>()
@@ -154,8 +147,8 @@ pub fn part_one(input: &str) -> Option<usize> {
     let rows = wh.map.rows();
     Some(wh.iter_boxes()
         .into_iter()
-        // Convert from internal bottom-left 0 to top-left 0
-        .map(|pos| 100 * (rows - pos.y() - 1) + pos.x())
+        .map(|pos| pos.math_to_screen(rows))
+        .map(|pos| 100 * pos.y() + pos.x())
         .sum())
 }
 
@@ -0,0 +1,278 @@
+//! Bounding boxes and coordinate compression for positions on a grid.
+//!
+//! A [`Rect`] replaces the `size` a solution tracks by hand and the bounds checks against it. [`Compressed`] maps sparse
+//! points, e.g. a few coordinates spread over millions of cells, onto a dense grid that only has a row and a column
+//! for each coordinate that occurs, so it can be stored in a [`crate::grid::GridSet`] or an array.
+
+use numeric::compound::vector::Vec2;
+use numeric::traits::class::Integral;
+
+/// A rectangle of positions, including both corners.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rect<I> {
+    min: Vec2<I>,
+    max: Vec2<I>,
+}
+
+impl<I: Integral> Rect<I> {
+    /// The rectangle from `min` to `max`, which must not be left of or below `min`.
+    pub fn new(min: Vec2<I>, max: Vec2<I>) -> Rect<I> {
+        assert!(
+            min.x() <= max.x() && min.y() <= max.y(),
+            "the corners of a rectangle are the wrong way around"
+        );
+        Rect { min, max }
+    }
+
+    /// The positions of a grid of `size` cells, from `(0, 0)` to `size - 1`. `None` if the grid has no cells.
+    pub fn from_size(size: Vec2<I>) -> Option<Rect<I>> {
+        if *size.x() <= I::zero() || *size.y() <= I::zero() {
+            return None;
+        }
+        Some(Rect {
+            min: Vec2::new([I::zero(), I::zero()]),
+            max: Vec2::from_xy(*size.x() - I::one(), *size.y() - I::one()),
+        })
+    }
+
+    /// The smallest rectangle containing all the points, or `None` if there are none.
+    pub fn bounding(points: impl IntoIterator<Item = Vec2<I>>) -> Option<Rect<I>> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let mut rect = Rect::new(first, first);
+        for point in points {
+            rect.include(point);
+        }
+        Some(rect)
+    }
+
+    pub fn min(&self) -> Vec2<I> {
+        self.min
+    }
+
+    pub fn max(&self) -> Vec2<I> {
+        self.max
+    }
+
+    pub fn contains(&self, pos: Vec2<I>) -> bool {
+        (self.min.x()..=self.max.x()).contains(&pos.x())
+            && (self.min.y()..=self.max.y()).contains(&pos.y())
+    }
+
+    /// Whether all of `other` is inside this rectangle.
+    pub fn contains_rect(&self, other: &Rect<I>) -> bool {
+        self.contains(other.min) && self.contains(other.max)
+    }
+
+    /// The positions in both rectangles, if there are any.
+    pub fn intersection(&self, other: &Rect<I>) -> Option<Rect<I>> {
+        let min = Vec2::from_xy(
+            *self.min.x().max(other.min.x()),
+            *self.min.y().max(other.min.y()),
+        );
+        let max = Vec2::from_xy(
+            *self.max.x().min(other.max.x()),
+            *self.max.y().min(other.max.y()),
+        );
+        (min.x() <= max.x() && min.y() <= max.y()).then_some(Rect { min, max })
+    }
+
+    /// The smallest rectangle containing both rectangles.
+    pub fn union(&self, other: &Rect<I>) -> Rect<I> {
+        let mut rect = *self;
+        rect.include(other.min);
+        rect.include(other.max);
+        rect
+    }
+
+    /// Grow the rectangle to contain `pos`.
+    pub fn include(&mut self, pos: Vec2<I>) {
+        self.min = Vec2::from_xy(*self.min.x().min(pos.x()), *self.min.y().min(pos.y()));
+        self.max = Vec2::from_xy(*self.max.x().max(pos.x()), *self.max.y().max(pos.y()));
+    }
+}
+
+impl<I: Integral + TryInto<usize> + TryFrom<usize>> Rect<I> {
+    /// The number of cells along each axis.
+    pub fn size(&self) -> Vec2<usize> {
+        let len = |min: I, max: I| match (max - min).try_into() {
+            Ok(len) => len + 1,
+            Err(_) => panic!("the rectangle is too large to index"),
+        };
+        Vec2::from_xy(
+            len(*self.min.x(), *self.max.x()),
+            len(*self.min.y(), *self.max.y()),
+        )
+    }
+
+    /// The index of `pos` if the rectangle's cells are numbered row by row from `min`, or `None` if it's outside.
+    pub fn index(&self, pos: Vec2<I>) -> Option<usize> {
+        if !self.contains(pos) {
+            return None;
+        }
+        let x: usize = (*pos.x() - *self.min.x()).try_into().ok()?;
+        let y: usize = (*pos.y() - *self.min.y()).try_into().ok()?;
+        Some(y * self.size().x() + x)
+    }
+
+    /// The position with an [`index`](Rect::index), or `None` if there are fewer cells.
+    pub fn position(&self, index: usize) -> Option<Vec2<I>> {
+        let size = self.size();
+        if index >= size.x() * size.y() {
+            return None;
+        }
+        let x = I::try_from(index % size.x()).ok()?;
+        let y = I::try_from(index / size.x()).ok()?;
+        Some(Vec2::from_xy(*self.min.x() + x, *self.min.y() + y))
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Sparse points mapped onto a dense grid. Each distinct x coordinate gets a column and each distinct y coordinate a
+/// row, in the same order as the coordinates, so positions keep their order along each axis.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Compressed<I> {
+    xs: Vec<I>,
+    ys: Vec<I>,
+}
+
+impl<I: Ord + Copy> Compressed<I> {
+    pub fn new(points: impl IntoIterator<Item = Vec2<I>>) -> Compressed<I> {
+        let (mut xs, mut ys): (Vec<_>, Vec<_>) = points
+            .into_iter()
+            .map(|point| (*point.x(), *point.y()))
+            .unzip();
+        for coords in [&mut xs, &mut ys] {
+            coords.sort_unstable();
+            coords.dedup();
+        }
+        Compressed { xs, ys }
+    }
+
+    /// The size of the dense grid.
+    pub fn size(&self) -> Vec2<usize> {
+        Vec2::from_xy(self.xs.len(), self.ys.len())
+    }
+
+    /// The dense position of `pos`, or `None` if one of its coordinates isn't in any of the points.
+    pub fn compress(&self, pos: Vec2<I>) -> Option<Vec2<usize>> {
+        let x = self.xs.binary_search(pos.x()).ok()?;
+        let y = self.ys.binary_search(pos.y()).ok()?;
+        Some(Vec2::from_xy(x, y))
+    }
+
+    /// The original position of a dense position, or `None` if it's outside the dense grid.
+    pub fn decompress(&self, pos: Vec2<usize>) -> Option<Vec2<I>> {
+        Some(Vec2::from_xy(
+            *self.xs.get(*pos.x())?,
+            *self.ys.get(*pos.y())?,
+        ))
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use numeric::compound::vector::Vec2;
+
+    use super::{Compressed, Rect};
+    use crate::prop::{check, i64_with_edges, vec_of};
+
+    fn rect(min: (i32, i32), max: (i32, i32)) -> Rect<i32> {
+        Rect::new(Vec2::from_xy(min.0, min.1), Vec2::from_xy(max.0, max.1))
+    }
+
+    #[test]
+    fn bounds_points() {
+        let points = [(3, -1), (-2, 4), (0, 0)].map(|(x, y)| Vec2::from_xy(x, y));
+        let bounds = Rect::bounding(points).unwrap();
+        assert_eq!(bounds, rect((-2, -1), (3, 4)));
+        assert!(points.iter().all(|&point| bounds.contains(point)));
+        assert!(!bounds.contains(Vec2::from_xy(4, 0)));
+        assert!(!bounds.contains(Vec2::from_xy(0, -2)));
+        assert_eq!(Rect::<i32>::bounding([]), None);
+
+        let grid = Rect::from_size(Vec2::from_xy(3u32, 2)).unwrap();
+        assert_eq!(grid.max(), Vec2::from_xy(2, 1));
+        assert_eq!(grid.size(), Vec2::from_xy(3, 2));
+        assert_eq!(Rect::from_size(Vec2::from_xy(0u32, 2)), None);
+    }
+
+    #[test]
+    fn intersects_rectangles() {
+        let a = rect((0, 0), (4, 4));
+        let b = rect((2, 3), (6, 5));
+        assert_eq!(a.intersection(&b), Some(rect((2, 3), (4, 4))));
+        assert_eq!(b.intersection(&a), a.intersection(&b));
+        assert_eq!(a.intersection(&rect((5, 0), (6, 4))), None);
+        assert_eq!(a.union(&b), rect((0, 0), (6, 5)));
+
+        assert!(a.contains_rect(&rect((1, 1), (4, 2))));
+        assert!(a.contains_rect(&a));
+        assert!(!a.contains_rect(&b));
+    }
+
+    #[test]
+    fn indexes_cells() {
+        let bounds = rect((-1, 2), (1, 3));
+        let cells = (0..6)
+            .map(|i| bounds.position(i).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            cells,
+            [(-1, 2), (0, 2), (1, 2), (-1, 3), (0, 3), (1, 3)].map(|(x, y)| Vec2::from_xy(x, y))
+        );
+        for (i, &cell) in cells.iter().enumerate() {
+            assert_eq!(bounds.index(cell), Some(i));
+        }
+        assert_eq!(bounds.position(6), None);
+        assert_eq!(bounds.index(Vec2::from_xy(2, 2)), None);
+    }
+
+    #[test]
+    fn compresses_sparse_points() {
+        let points = [(1000, 5), (-7, 5), (1000, 1_000_000)].map(|(x, y)| Vec2::from_xy(x, y));
+        let compressed = Compressed::new(points);
+        assert_eq!(compressed.size(), Vec2::from_xy(2, 2));
+        assert_eq!(
+            points.map(|point| compressed.compress(point).unwrap()),
+            [(1, 0), (0, 0), (1, 1)].map(|(x, y)| Vec2::from_xy(x, y))
+        );
+        for point in points {
+            let dense = compressed.compress(point).unwrap();
+            assert_eq!(compressed.decompress(dense), Some(point));
+        }
+        assert_eq!(compressed.compress(Vec2::from_xy(0, 5)), None);
+        assert_eq!(compressed.decompress(Vec2::from_xy(2, 0)), None);
+    }
+
+    #[test]
+    fn compression_round_trips_and_keeps_order() {
+        check(
+            |rng| {
+                vec_of(rng, 1..=20, |rng| {
+                    Vec2::from_xy(i64_with_edges(rng), i64_with_edges(rng))
+                })
+            },
+            |points| {
+                let compressed = Compressed::new(points.iter().copied());
+                let dense = points
+                    .iter()
+                    .map(|&point| compressed.compress(point).unwrap())
+                    .collect::<Vec<_>>();
+                for (&point, &pos) in points.iter().zip(&dense) {
+                    assert!(pos.x() < compressed.size().x() && pos.y() < compressed.size().y());
+                    assert_eq!(compressed.decompress(pos), Some(point));
+                }
+                for (a, da) in points.iter().zip(&dense) {
+                    for (b, db) in points.iter().zip(&dense) {
+                        assert_eq!(a.x().cmp(b.x()), da.x().cmp(db.x()));
+                        assert_eq!(a.y().cmp(b.y()), da.y().cmp(db.y()));
+                    }
+                }
+            },
+        );
+    }
+}
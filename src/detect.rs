@@ -0,0 +1,245 @@
+//! Heuristics for how much structure a snapshot of a grid has, for puzzles like day 14 that ask when a simulation
+//! draws a picture.
+//!
+//! Noise spreads points evenly over the grid, and a picture gathers them in one place. Each detector measures that in a
+//! different way: the points of a picture touch ([`largest_component`]), sit close together on each axis
+//! ([`variance`]), fill few parts of the grid ([`entropy`]) and crowd into one quadrant ([`safety_factor`]). Scoring
+//! every step of a simulation with one of them and taking the [`most_structured`] step finds the picture without
+//! knowing what it looks like. Noise can beat a faint picture on one detector, so steps can also be [`ranked`] and
+//! tried in order until a second detector confirms one.
+
+use numeric::compound::vector::Vec2;
+
+use crate::grid::GridSet;
+use crate::Neighbours;
+
+/// The number of cells in the largest group of cells connected horizontally or vertically.
+pub fn largest_component(cells: &GridSet) -> usize {
+    let mut seen = GridSet::new(cells.size());
+    let mut stack = Vec::new();
+    let mut largest = 0;
+    for start in cells.iter() {
+        if !seen.insert(start) {
+            continue;
+        }
+        stack.push(start);
+        let mut size = 0;
+        while let Some(pos) = stack.pop() {
+            size += 1;
+            for next in pos.neighbours4() {
+                if cells.contains(next) && seen.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+        largest = largest.max(size);
+    }
+    largest
+}
+
+/// The variance of the x and of the y coordinates of the points, or `None` if there are none. The axes are separate so
+/// they can be minimised separately, e.g. when the x coordinates repeat with a different period than the y coordinates.
+pub fn variance(points: impl IntoIterator<Item = Vec2<i64>>) -> Option<Vec2<f64>> {
+    let mut count = 0.0;
+    let [mut sum_x, mut sum_y, mut sum_sq_x, mut sum_sq_y] = [0.0; 4];
+    for point in points {
+        let (x, y) = (*point.x() as f64, *point.y() as f64);
+        count += 1.0;
+        sum_x += x;
+        sum_y += y;
+        sum_sq_x += x * x;
+        sum_sq_y += y * y;
+    }
+    if count == 0.0 {
+        return None;
+    }
+    let var = |sum: f64, sum_sq: f64| sum_sq / count - (sum / count).powi(2);
+    Some(Vec2::from_xy(var(sum_x, sum_sq_x), var(sum_y, sum_sq_y)))
+}
+
+/// The Shannon entropy, in bits, of how the points are spread over square tiles of `tile` cells in a grid of `size`.
+/// Points outside the grid are ignored. Noise has close to the maximum of `log2(tiles)`, a picture much less.
+pub fn entropy(points: impl IntoIterator<Item = Vec2<i64>>, size: Vec2<usize>, tile: usize) -> f64 {
+    assert!(tile > 0, "tiles must contain at least one cell");
+    let tiles = Vec2::from_xy(size.x().div_ceil(tile), size.y().div_ceil(tile));
+    let mut counts = vec![0usize; tiles.x() * tiles.y()];
+    let mut total = 0;
+    for point in points {
+        let (Ok(x), Ok(y)) = (usize::try_from(*point.x()), usize::try_from(*point.y())) else {
+            continue;
+        };
+        if x >= *size.x() || y >= *size.y() {
+            continue;
+        }
+        counts[(y / tile) * tiles.x() + x / tile] += 1;
+        total += 1;
+    }
+
+    counts
+        .into_iter()
+        .filter(|&count| count > 0)
+        .map(|count| {
+            let p = count as f64 / total as f64;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// The product of the number of points in each quadrant of a grid of `size`. Points on the middle row or column don't
+/// count. Day 14 asks for this after 100 steps, and a picture in one quadrant makes it unusually small.
+pub fn safety_factor(points: impl IntoIterator<Item = Vec2<i64>>, size: Vec2<i64>) -> u64 {
+    let middle = Vec2::from_xy(size.x() / 2, size.y() / 2);
+    let mut quadrants = [0; 4];
+    for point in points {
+        if point.x() == middle.x() || point.y() == middle.y() {
+            continue;
+        }
+        let left = point.x() < middle.x();
+        let top = point.y() < middle.y();
+        quadrants[usize::from(left) * 2 + usize::from(top)] += 1;
+    }
+    quadrants.into_iter().product()
+}
+
+/// The step with the lowest score, or `None` if there are no steps. The earliest step wins a tie.
+pub fn most_structured<S: PartialOrd>(
+    steps: impl IntoIterator<Item = u64>,
+    mut score: impl FnMut(u64) -> S,
+) -> Option<u64> {
+    let mut best: Option<(u64, S)> = None;
+    for step in steps {
+        let current = score(step);
+        if best.as_ref().is_none_or(|(_, best)| current < *best) {
+            best = Some((step, current));
+        }
+    }
+    best.map(|(step, _)| step)
+}
+
+/// The steps from the lowest to the highest score, earlier steps first on a tie. For when the step with the lowest
+/// score needs confirming and the next best should be tried if it isn't.
+pub fn ranked<S: PartialOrd>(
+    steps: impl IntoIterator<Item = u64>,
+    mut score: impl FnMut(u64) -> S,
+) -> Vec<u64> {
+    let mut scored = steps
+        .into_iter()
+        .map(|step| (score(step), step))
+        .collect::<Vec<_>>();
+    scored.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    scored.into_iter().map(|(_, step)| step).collect()
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use numeric::compound::vector::Vec2;
+
+    use super::{entropy, largest_component, most_structured, ranked, safety_factor, variance};
+    use crate::grid::GridSet;
+
+    fn points(picture: &str) -> Vec<Vec2<i64>> {
+        picture
+            .lines()
+            .enumerate()
+            .flat_map(|(y, line)| {
+                line.chars()
+                    .enumerate()
+                    .filter(|&(_, c)| c == '#')
+                    .map(move |(x, _)| Vec2::from_xy(x as i64, y as i64))
+            })
+            .collect()
+    }
+
+    const PICTURE: &str = "\
+.......
+..#....
+.###...
+#####..
+..#....
+.......";
+
+    const NOISE: &str = "\
+#.....#
+...#...
+.....#.
+.#.....
+....#.#
+#..#...";
+
+    #[test]
+    fn finds_the_largest_component() {
+        let mut set = GridSet::new(Vec2::from_xy(7, 6));
+        for point in points(PICTURE) {
+            set.insert(point);
+        }
+        assert_eq!(largest_component(&set), 10);
+
+        set.insert(Vec2::from_xy(6u32, 0));
+        set.insert(Vec2::from_xy(5u32, 0));
+        assert_eq!(largest_component(&set), 10);
+
+        let mut noise = GridSet::new(Vec2::from_xy(7, 6));
+        for point in points(NOISE) {
+            noise.insert(point);
+        }
+        assert_eq!(largest_component(&noise), 1);
+        assert_eq!(largest_component(&GridSet::new(Vec2::from_xy(3, 3))), 0);
+    }
+
+    #[test]
+    fn pictures_have_low_variance() {
+        let var = variance([Vec2::from_xy(0, 4), Vec2::from_xy(2, 4)]).unwrap();
+        assert_eq!((*var.x(), *var.y()), (1.0, 0.0));
+        assert!(variance([]).is_none());
+
+        let picture = variance(points(PICTURE)).unwrap();
+        let noise = variance(points(NOISE)).unwrap();
+        assert!(picture.x() < noise.x());
+        assert!(picture.y() < noise.y());
+    }
+
+    #[test]
+    fn pictures_have_low_entropy() {
+        let size = Vec2::from_xy(7, 6);
+        assert_eq!(entropy(points(PICTURE), size, 7), 0.0);
+        assert_eq!(
+            entropy([Vec2::from_xy(0, 0), Vec2::from_xy(6, 5)], size, 1),
+            1.0
+        );
+        assert_eq!(
+            entropy([Vec2::from_xy(-1, 0), Vec2::from_xy(7, 0)], size, 1),
+            0.0
+        );
+        assert!(entropy(points(PICTURE), size, 2) < entropy(points(NOISE), size, 2));
+    }
+
+    #[test]
+    fn multiplies_quadrants() {
+        let size = Vec2::from_xy(7, 6);
+        assert_eq!(safety_factor(points(NOISE), size), 4);
+        assert_eq!(safety_factor(points(PICTURE), size), 0);
+        // The middle row and column don't count
+        assert_eq!(
+            safety_factor(
+                [(0, 0), (6, 0), (0, 5), (6, 5), (3, 0), (0, 3)].map(|(x, y)| Vec2::from_xy(x, y)),
+                size
+            ),
+            1
+        );
+    }
+
+    #[test]
+    fn picks_the_first_lowest_score() {
+        assert_eq!(
+            most_structured(0..6, |step| (step as i64 - 3).abs() / 2),
+            Some(2)
+        );
+        assert_eq!(most_structured(0..0, |step| step), None);
+        assert_eq!(
+            ranked(0..6, |step| (step as i64 - 3).abs() / 2),
+            [2, 3, 4, 0, 1, 5]
+        );
+    }
+}
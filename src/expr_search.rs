@@ -0,0 +1,243 @@
+//! Search for operators that make an equation like day 07's `3267: 81 40 27` true.
+//!
+//! Operators are evaluated left to right, so [`solvable`] searches them right to left: the last operand must have been
+//! combined with the value of everything before it, and an [`Operator`] can tell which values that could have been.
+//! Most operators rule out most values (the answer must be divisible by the operand for [`Op::Mul`], end in its digits
+//! for [`Op::Concat`] and be at least the operand for [`Op::Add`]), which prunes almost the whole search.
+//! [`brute_force`] tries every assignment with [`crate::fast_cartesian`] and is kept as the reference.
+
+use gat_lending_iterator::LendingIterator;
+
+use crate::fast_cartesian::IntoLendingExt;
+
+/// A binary operator on `u64`s, which can be undone given its result and right-hand side.
+pub trait Operator: Copy {
+    /// `lhs op rhs`, or `None` if it overflows.
+    fn apply(self, lhs: u64, rhs: u64) -> Option<u64>;
+
+    /// The left-hand side for which `lhs op rhs == result`.
+    fn undo(self, result: u64, rhs: u64) -> Undo;
+}
+
+/// The left-hand sides an [`Operator`] could have been applied to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Undo {
+    /// No left-hand side gives the result.
+    Impossible,
+    /// Only this left-hand side gives the result.
+    Lhs(u64),
+    /// Every left-hand side gives the result, e.g. for `lhs * 0 == 0`.
+    AnyLhs,
+}
+
+/// The operators of day 07.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Op {
+    Add,
+    Mul,
+    /// Concatenates the digits, e.g. `12 || 345 == 12345`.
+    Concat,
+}
+
+impl Operator for Op {
+    fn apply(self, lhs: u64, rhs: u64) -> Option<u64> {
+        match self {
+            Op::Add => lhs.checked_add(rhs),
+            Op::Mul => lhs.checked_mul(rhs),
+            Op::Concat => match digits_shift(rhs) {
+                Some(shift) => lhs.checked_mul(shift)?.checked_add(rhs),
+                None => (lhs == 0).then_some(rhs),
+            },
+        }
+    }
+
+    fn undo(self, result: u64, rhs: u64) -> Undo {
+        match self {
+            Op::Add => result.checked_sub(rhs).map_or(Undo::Impossible, Undo::Lhs),
+            Op::Mul if rhs == 0 => {
+                if result == 0 {
+                    Undo::AnyLhs
+                } else {
+                    Undo::Impossible
+                }
+            }
+            Op::Mul if result.is_multiple_of(rhs) => Undo::Lhs(result / rhs),
+            Op::Mul => Undo::Impossible,
+            Op::Concat => match digits_shift(rhs) {
+                Some(shift) if result % shift == rhs => Undo::Lhs(result / shift),
+                // `rhs` has 20 digits, so only `0 || rhs` gives it.
+                None if result == rhs => Undo::Lhs(0),
+                _ => Undo::Impossible,
+            },
+        }
+    }
+}
+
+/// `10^digits`, the factor `Concat` shifts the left-hand side by, if it fits in a `u64`.
+fn digits_shift(rhs: u64) -> Option<u64> {
+    10u64.checked_pow(rhs.checked_ilog10().unwrap_or(0) + 1)
+}
+
+/// Evaluate `operands` left to right with `ops` between them, or `None` if it overflows.
+pub fn evaluate<O: Operator>(operands: &[u64], ops: &[O]) -> Option<u64> {
+    let (&first, rest) = operands.split_first()?;
+    assert_eq!(ops.len(), rest.len(), "one operator between each operand");
+    ops.iter()
+        .zip(rest)
+        .try_fold(first, |acc, (op, &rhs)| op.apply(acc, rhs))
+}
+
+/// Whether some assignment of `ops` between the `operands` evaluates to `target`.
+pub fn solvable<O: Operator>(target: u64, operands: &[u64], ops: &[O]) -> bool {
+    match operands {
+        [] => false,
+        [first] => *first == target,
+        [rest @ .., last] => ops.iter().any(|op| match op.undo(target, *last) {
+            Undo::Impossible => false,
+            Undo::Lhs(lhs) => solvable(lhs, rest, ops),
+            Undo::AnyLhs => evaluates(rest[0], &rest[1..], ops),
+        }),
+    }
+}
+
+/// Whether some assignment of `ops` evaluates without overflowing.
+fn evaluates<O: Operator>(acc: u64, operands: &[u64], ops: &[O]) -> bool {
+    match operands {
+        [] => true,
+        [next, rest @ ..] => ops.iter().any(|op| {
+            op.apply(acc, *next)
+                .is_some_and(|acc| evaluates(acc, rest, ops))
+        }),
+    }
+}
+
+/// Like [`solvable`], but evaluates every assignment of `ops`.
+pub fn brute_force<O: Operator>(target: u64, operands: &[u64], ops: &[O]) -> bool {
+    if operands.is_empty() {
+        return false;
+    }
+    let mut assignments = operands[1..]
+        .iter()
+        .map(|_| ops.iter().copied())
+        .multi_cartesian_product();
+    // `any` would need the assignments to be `'static`, as its closure takes items of any lifetime.
+    while let Some(assignment) = assignments.next() {
+        if evaluate(operands, assignment) == Some(target) {
+            return true;
+        }
+    }
+    false
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{brute_force, evaluate, solvable, Op, Operator, Undo};
+    use crate::gen::Rng;
+    use crate::prop::{check, vec_of};
+
+    const ALL: [Op; 3] = [Op::Add, Op::Mul, Op::Concat];
+
+    #[test]
+    fn solves_the_example() {
+        let equations: [(u64, &[u64], bool, bool); 9] = [
+            (190, &[10, 19], true, true),
+            (3267, &[81, 40, 27], true, true),
+            (83, &[17, 5], false, false),
+            (156, &[15, 6], false, true),
+            (7290, &[6, 8, 6, 15], false, true),
+            (161011, &[16, 10, 13], false, false),
+            (192, &[17, 8, 14], false, true),
+            (21037, &[9, 7, 18, 13], false, false),
+            (292, &[11, 6, 16, 20], true, true),
+        ];
+        for (target, operands, add_mul, any) in equations {
+            assert_eq!(
+                solvable(target, operands, &[Op::Add, Op::Mul]),
+                add_mul,
+                "{target}"
+            );
+            assert_eq!(solvable(target, operands, &ALL), any, "{target}");
+        }
+    }
+
+    #[test]
+    fn undoes_what_it_applies() {
+        check(
+            |rng| {
+                let op = ALL[rng.range(0..=2) as usize];
+                (op, rng.range(0..=1_000_000), rng.range(0..=1000))
+            },
+            |&(op, lhs, rhs)| {
+                let result = op.apply(lhs, rhs).unwrap();
+                match op.undo(result, rhs) {
+                    Undo::Lhs(undone) => assert_eq!(undone, lhs),
+                    Undo::AnyLhs => assert_eq!(op.apply(lhs + 1, rhs), Some(result)),
+                    Undo::Impossible => panic!("{op:?} can't be undone"),
+                }
+            },
+        );
+        assert_eq!(Op::Concat.apply(0, u64::MAX), Some(u64::MAX));
+        assert_eq!(Op::Concat.undo(u64::MAX, u64::MAX), Undo::Lhs(0));
+    }
+
+    /// Up to five small operands, which are often zero, and a target that is reachable about half of the time.
+    fn equation(rng: &mut Rng) -> (u64, Vec<u64>) {
+        let operands = vec_of(rng, 0..=5, |rng| {
+            if rng.chance(10) {
+                0
+            } else {
+                rng.range(1..=20)
+            }
+        });
+        let ops = vec_of(rng, 4..=4, |rng| ALL[rng.range(0..=2) as usize]);
+        let reachable = ops
+            .get(..operands.len().saturating_sub(1))
+            .and_then(|ops| evaluate(&operands, ops));
+        let target = match reachable {
+            Some(target) if rng.chance(50) => target,
+            _ => rng.range(0..=500),
+        };
+        (target, operands)
+    }
+
+    #[test]
+    fn agrees_with_brute_force() {
+        check(equation, |(target, operands)| {
+            for ops in [&ALL[..], &[Op::Add, Op::Mul], &[Op::Concat]] {
+                assert_eq!(
+                    solvable(*target, operands, ops),
+                    brute_force(*target, operands, ops),
+                    "{ops:?}"
+                );
+            }
+        });
+    }
+
+    /// An operator defined outside the module, to check that the search isn't tied to [`Op`].
+    #[derive(Copy, Clone, Debug)]
+    struct Xor;
+
+    impl Operator for Xor {
+        fn apply(self, lhs: u64, rhs: u64) -> Option<u64> {
+            Some(lhs ^ rhs)
+        }
+
+        fn undo(self, result: u64, rhs: u64) -> Undo {
+            Undo::Lhs(result ^ rhs)
+        }
+    }
+
+    #[test]
+    fn searches_other_operators() {
+        assert!(solvable(0b110, &[0b011, 0b101], &[Xor]));
+        assert!(!solvable(0b111, &[0b011, 0b101], &[Xor]));
+        check(equation, |(target, operands)| {
+            assert_eq!(
+                solvable(*target, operands, &[Xor]),
+                brute_force(*target, operands, &[Xor])
+            );
+        });
+    }
+}
@@ -19,12 +19,52 @@ pub trait IntoLendingExt: ToLendingIterator {
 
 impl<L: ToLendingIterator> IntoLendingExt for L {}
 
+pub trait ArrayLendingExt<T: IntoIterator, const N: usize> {
+    fn array_cartesian_product(self) -> ArrayProduct<T::IntoIter, N>
+    where
+        T::IntoIter: Clone,
+        T::Item: Clone;
+}
+
+impl<T: IntoIterator, const N: usize> ArrayLendingExt<T, N> for [T; N] {
+    fn array_cartesian_product(self) -> ArrayProduct<T::IntoIter, N>
+    where
+        T::IntoIter: Clone,
+        T::Item: Clone,
+    {
+        array_cartesian_product(self)
+    }
+}
+
+/// A lending iterator that stops yielding items after returning `None` once, like [`std::iter::FusedIterator`].
+pub trait FusedLendingIterator: LendingIterator {}
+
+/// A lending iterator that knows its exact length, like [`ExactSizeIterator`].
+pub trait ExactSizeLendingIterator: LendingIterator {
+    /// The number of items left. Panics if it doesn't fit in a `usize`, which a product of a few dozen iterators
+    /// already exceeds. The size hint has no upper bound then.
+    fn len(&self) -> usize {
+        let (lower, upper) = self.size_hint();
+        let len = upper.expect("the length overflows usize");
+        debug_assert_eq!(lower, len);
+        len
+    }
+
+    /// Whether there are no items left. Unlike [`len`](ExactSizeLendingIterator::len), this works for any length.
+    fn is_empty(&self) -> bool {
+        self.size_hint().0 == 0
+    }
+}
+
 #[derive(Clone)]
 /// An iterator adaptor that iterates over the cartesian product of
 /// multiple iterators of type `I`.
 ///
 /// An iterator element type is `Vec<I::Item>`.
 ///
+/// A product of no iterators has a single, empty item, like [`ArrayProduct`] with `N = 0` and like the empty product
+/// in maths. Before the `unsafe` was removed from `next` it had no items.
+///
 /// See [`.multi_cartesian_product()`](crate::Itertools::multi_cartesian_product)
 /// for more information.
 #[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
@@ -105,64 +145,46 @@ where
         let inner = self.0.as_mut()?;
         match &mut inner.cur {
             Populated(values) => {
-                debug_assert!(!inner.iters.is_empty());
                 // Find (from the right) a non-finished iterator and
                 // reset the finished ones encountered.
+                let mut advanced = false;
                 for (iter, item) in inner.iters.iter_mut().zip(values.iter_mut()).rev() {
                     if let Some(new) = iter.iter.next() {
                         *item = new;
-                        // SAFETY: Polonius
-                        return Some(unsafe { core::mem::transmute::<&[_], &[_]>(values) });
-                    } else {
-                        iter.iter = iter.iter_orig.clone();
-                        // `cur` is populated so the untouched `iter_orig` can not be empty.
-                        *item = iter.iter.next().unwrap();
+                        advanced = true;
+                        break;
                     }
+                    iter.iter = iter.iter_orig.clone();
+                    // `cur` is populated so the untouched `iter_orig` can not be empty.
+                    *item = iter.iter.next().unwrap();
+                }
+                if !advanced {
+                    self.0 = ProductEnded;
+                    return None;
                 }
-                self.0 = ProductEnded;
-                None
             }
             // Only the first time.
             NotYetPopulated => {
                 let next: Option<Vec<_>> = inner.iters.iter_mut().map(|i| i.iter.next()).collect();
-                if next.is_none() || inner.iters.is_empty() {
-                    // This cartesian product had at most one item to generate and now ends.
+                if next.is_none() {
+                    // One of the iterators is empty, so the product is empty.
                     self.0 = ProductEnded;
-                    None
-                } else {
-                    inner.cur = next;
-                    inner
-                        .cur
-                        .as_deref()
-                        .map(|val| unsafe { core::mem::transmute::<&[_], &[_]>(val) })
+                    return None;
                 }
+                // A product of no iterators has a single, empty item.
+                inner.cur = next;
             }
         }
+        // The current items are only borrowed here, after updating them. Returning them from inside the loop would
+        // keep `self` borrowed on the paths that end the product, which the borrow checker rejects.
+        self.0.as_ref()?.cur.as_deref()
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
         match &self.0 {
             ProductEnded => (0, Some(0)),
-            ProductInProgress(MultiProductInner {
-                iters,
-                cur: NotYetPopulated,
-            }) => iters
-                .iter()
-                .map(|iter| iter.iter_orig.size_hint())
-                .fold((1, Some(1)), size_hint::mul),
-            ProductInProgress(MultiProductInner {
-                iters,
-                cur: Populated(_),
-            }) => {
-                if let [first, tail @ ..] = &iters[..] {
-                    tail.iter().fold(first.iter.size_hint(), |mut sh, iter| {
-                        sh = size_hint::mul(sh, iter.iter_orig.size_hint());
-                        size_hint::add(sh, iter.iter.size_hint())
-                    })
-                } else {
-                    // Since it is populated, this cartesian product has started so `iters` is not empty.
-                    unreachable!()
-                }
+            ProductInProgress(MultiProductInner { iters, cur }) => {
+                product_size_hint(iters, cur.is_some())
             }
         }
     }
@@ -170,67 +192,473 @@ where
     fn count(self) -> usize {
         match self.0 {
             ProductEnded => 0,
-            // The iterator is fresh so the count is the product of the length of each iterator:
-            // - If one of them is empty, stop counting.
-            // - Less `count()` calls than the general case.
-            ProductInProgress(MultiProductInner {
-                iters,
-                cur: NotYetPopulated,
-            }) => iters
+            ProductInProgress(MultiProductInner { iters, cur }) => {
+                product_count(iters, cur.is_some())
+            }
+        }
+    }
+}
+
+impl<I> MultiProduct<I>
+where
+    I: Iterator + Clone,
+    I::Item: Clone,
+{
+    /// Skips `n` items and returns the next one, like [`Iterator::nth`].
+    pub fn nth(&mut self, n: usize) -> Option<&[I::Item]> {
+        for _ in 0..n {
+            self.next()?;
+        }
+        self.next()
+    }
+
+    /// Consumes the product and returns its last item, without iterating over the items in between.
+    pub fn last(self) -> Option<Vec<I::Item>> {
+        let MultiProductInner { iters, cur } = self.0?;
+        // Collect the last item of each iterator of the product.
+        if let Populated(values) = cur {
+            let mut count = iters.len();
+            let last = iters
                 .into_iter()
-                .map(|iter| iter.iter_orig.count())
-                .try_fold(1, |product, count| {
-                    if count == 0 {
-                        None
-                    } else {
-                        Some(product * count)
+                .zip(values)
+                .map(|(i, value)| {
+                    i.iter.last().unwrap_or_else(|| {
+                        // The iterator is empty, use its current `value`.
+                        count -= 1;
+                        value
+                    })
+                })
+                .collect();
+            if count == 0 {
+                // `values` was the last item.
+                None
+            } else {
+                Some(last)
+            }
+        } else {
+            iters.into_iter().map(|i| i.iter.last()).collect()
+        }
+    }
+}
+
+impl<I> FusedLendingIterator for MultiProduct<I>
+where
+    I: Iterator + Clone,
+    I::Item: Clone,
+{
+}
+
+impl<I> ExactSizeLendingIterator for MultiProduct<I>
+where
+    I: ExactSizeIterator + Clone,
+    I::Item: Clone,
+{
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// An iterator adaptor that iterates over the cartesian product of `N` iterators of type `I`.
+///
+/// Like [`MultiProduct`], but the number of iterators is known at compile time, so items are lent as `&[I::Item; N]`.
+#[derive(Clone)]
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct ArrayProduct<I, const N: usize>
+where
+    I: Iterator + Clone,
+    I::Item: Clone,
+{
+    iters: [MultiProductIter<I>; N],
+    /// Not populated at the beginning then it holds the current item of each iterator.
+    cur: CurrentItems<[I::Item; N]>,
+    ended: bool,
+}
+
+/// Create a new cartesian product iterator over `N` iterators of the same type.
+///
+/// Iterator element is of type `[T::Item; N]`.
+pub fn array_cartesian_product<T, const N: usize>(iters: [T; N]) -> ArrayProduct<T::IntoIter, N>
+where
+    T: IntoIterator,
+    T::IntoIter: Clone,
+    T::Item: Clone,
+{
+    ArrayProduct {
+        iters: iters.map(|i| MultiProductIter::new(i.into_iter())),
+        cur: NotYetPopulated,
+        ended: false,
+    }
+}
+
+impl<I, const N: usize> LendingIterator for ArrayProduct<I, N>
+where
+    I: Iterator + Clone,
+    I::Item: Clone,
+{
+    type Item<'a>
+        = &'a [I::Item; N]
+    where
+        I: 'a;
+
+    fn next(&mut self) -> Option<Self::Item<'_>> {
+        // This fuses the iterator.
+        if self.ended {
+            return None;
+        }
+        match &mut self.cur {
+            Populated(values) => {
+                // Find (from the right) a non-finished iterator and
+                // reset the finished ones encountered.
+                let mut advanced = false;
+                for (iter, item) in self.iters.iter_mut().zip(values.iter_mut()).rev() {
+                    if let Some(new) = iter.iter.next() {
+                        *item = new;
+                        advanced = true;
+                        break;
                     }
+                    iter.iter = iter.iter_orig.clone();
+                    // `cur` is populated so the untouched `iter_orig` can not be empty.
+                    *item = iter.iter.next().unwrap();
+                }
+                if !advanced {
+                    self.ended = true;
+                    return None;
+                }
+            }
+            // Only the first time.
+            NotYetPopulated => {
+                let first = self.iters.each_mut().map(|i| i.iter.next());
+                if first.iter().any(Option::is_none) {
+                    self.ended = true;
+                    return None;
+                }
+                self.cur = Populated(first.map(Option::unwrap));
+            }
+        }
+        // The current items are only returned here, after updating them, so no borrow has to outlive the loop.
+        self.cur.as_ref()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.ended {
+            (0, Some(0))
+        } else {
+            product_size_hint(&self.iters, self.cur.is_some())
+        }
+    }
+
+    fn count(self) -> usize {
+        if self.ended {
+            0
+        } else {
+            product_count(self.iters, self.cur.is_some())
+        }
+    }
+}
+
+impl<I, const N: usize> ArrayProduct<I, N>
+where
+    I: Iterator + Clone,
+    I::Item: Clone,
+{
+    /// Skips `n` items and returns the next one, like [`Iterator::nth`].
+    pub fn nth(&mut self, n: usize) -> Option<&[I::Item; N]> {
+        for _ in 0..n {
+            self.next()?;
+        }
+        self.next()
+    }
+
+    /// Consumes the product and returns its last item, without iterating over the items in between.
+    pub fn last(self) -> Option<[I::Item; N]> {
+        if self.ended {
+            return None;
+        }
+        match self.cur {
+            Populated(values) => {
+                let mut remaining = false;
+                let last = self.iters.into_iter().zip(values).map(|(i, value)| {
+                    // An empty iterator is at its last item already.
+                    i.iter.last().inspect(|_| remaining = true).unwrap_or(value)
+                });
+                let last: [I::Item; N] = collect_array(last);
+                // otherwise, `values` was the last item.
+                remaining.then_some(last)
+            }
+            NotYetPopulated => {
+                let last = self.iters.map(|i| i.iter.last());
+                if last.iter().any(Option::is_none) {
+                    None
+                } else {
+                    Some(last.map(Option::unwrap))
+                }
+            }
+        }
+    }
+}
+
+impl<I, const N: usize> FusedLendingIterator for ArrayProduct<I, N>
+where
+    I: Iterator + Clone,
+    I::Item: Clone,
+{
+}
+
+impl<I, const N: usize> ExactSizeLendingIterator for ArrayProduct<I, N>
+where
+    I: ExactSizeIterator + Clone,
+    I::Item: Clone,
+{
+}
+
+fn collect_array<T, const N: usize>(mut iter: impl Iterator<Item = T>) -> [T; N] {
+    std::array::from_fn(|_| iter.next().expect("iterator has N items"))
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// The size hint of a product of `iters`. `started` is set once the first item was returned.
+fn product_size_hint<I>(iters: &[MultiProductIter<I>], started: bool) -> size_hint::SizeHint
+where
+    I: Iterator + Clone,
+    I::Item: Clone,
+{
+    if !started {
+        return iters
+            .iter()
+            .map(|iter| iter.iter_orig.size_hint())
+            .fold((1, Some(1)), size_hint::mul);
+    }
+
+    if let [first, tail @ ..] = iters {
+        tail.iter().fold(first.iter.size_hint(), |mut sh, iter| {
+            sh = size_hint::mul(sh, iter.iter_orig.size_hint());
+            size_hint::add(sh, iter.iter.size_hint())
+        })
+    } else {
+        // Only the single, empty item of a product of no iterators.
+        (0, Some(0))
+    }
+}
+
+/// The number of items left in a product of `iters`. `started` is set once the first item was returned.
+fn product_count<I>(iters: impl IntoIterator<Item = MultiProductIter<I>>, started: bool) -> usize
+where
+    I: Iterator + Clone,
+    I::Item: Clone,
+{
+    if !started {
+        // The iterator is fresh so the count is the product of the length of each iterator:
+        // - If one of them is empty, stop counting.
+        // - Less `count()` calls than the general case.
+        return iters
+            .into_iter()
+            .map(|iter| iter.iter_orig.count())
+            .try_fold(1, |product, count| {
+                if count == 0 {
+                    None
+                } else {
+                    Some(product * count)
+                }
+            })
+            .unwrap_or_default();
+    }
+
+    // The general case.
+    iters.into_iter().fold(0, |mut acc, iter| {
+        if acc != 0 {
+            acc *= iter.iter_orig.count();
+        }
+        acc + iter.iter.count()
+    })
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use gat_lending_iterator::LendingIterator;
+
+    use super::{
+        array_cartesian_product, multi_cartesian_product, ArrayLendingExt, ExactSizeLendingIterator,
+    };
+    use crate::gen::Rng;
+    use crate::prop::{check, vec_of};
+
+    /// Up to four factors with up to four items each.
+    fn factors(rng: &mut Rng) -> Vec<Vec<u8>> {
+        vec_of(rng, 0..=4, |rng| {
+            vec_of(rng, 0..=4, |rng| rng.range(0..=9) as u8)
+        })
+    }
+
+    /// The cartesian product, computed the obvious way.
+    fn product(factors: &[Vec<u8>]) -> Vec<Vec<u8>> {
+        factors.iter().fold(vec![vec![]], |items, factor| {
+            items
+                .iter()
+                .flat_map(|item| {
+                    factor.iter().map(move |&value| {
+                        let mut item = item.clone();
+                        item.push(value);
+                        item
+                    })
                 })
-                .unwrap_or_default(),
-            // The general case.
-            ProductInProgress(MultiProductInner {
-                iters,
-                cur: Populated(_),
-            }) => iters.into_iter().fold(0, |mut acc, iter| {
-                if acc != 0 {
-                    acc *= iter.iter_orig.count();
+                .collect()
+        })
+    }
+
+    #[test]
+    fn yields_the_cartesian_product() {
+        check(factors, |factors| {
+            let mut iter = multi_cartesian_product(factors.iter().cloned());
+            let mut items = vec![];
+            while let Some(item) = iter.next() {
+                items.push(item.to_vec());
+            }
+            assert_eq!(items, product(factors));
+            assert!(iter.next().is_none(), "not fused");
+        });
+    }
+
+    #[test]
+    fn size_hint_and_count_agree_with_iteration() {
+        check(
+            |rng| {
+                let factors = factors(rng);
+                let len = product(&factors).len() as u64;
+                (factors, rng.range(0..=len) as usize)
+            },
+            |(factors, skip)| {
+                let mut iter = multi_cartesian_product(factors.iter().cloned());
+                for _ in 0..*skip {
+                    iter.next();
                 }
-                acc + iter.iter.count()
-            }),
+                let remaining = product(factors).len() - skip;
+
+                let (low, high) = iter.size_hint();
+                assert!(low <= remaining, "lower bound {low} > {remaining}");
+                assert!(
+                    high.is_none_or(|high| high >= remaining),
+                    "upper bound {high:?} < {remaining}"
+                );
+                assert_eq!(iter.clone().count(), remaining);
+            },
+        );
+    }
+
+    /// Three factors with up to four items each, and the number of items to skip.
+    fn three_factors(rng: &mut Rng) -> ([Vec<u8>; 3], usize) {
+        let factors = std::array::from_fn(|_| vec_of(rng, 0..=4, |rng| rng.range(0..=9) as u8));
+        let len = product(&factors).len() as u64;
+        (factors, rng.range(0..=len) as usize)
+    }
+
+    #[test]
+    fn array_product_yields_the_cartesian_product() {
+        check(three_factors, |(factors, skip)| {
+            let expected = product(factors);
+
+            let mut iter = factors.clone().array_cartesian_product();
+            assert_eq!(iter.len(), expected.len());
+            let mut items = vec![];
+            while let Some(item) = iter.next() {
+                items.push(item.to_vec());
+            }
+            assert_eq!(items, expected);
+            assert!(iter.next().is_none(), "not fused");
+
+            let mut iter = array_cartesian_product(factors.clone());
+            for _ in 0..*skip {
+                iter.next();
+            }
+            assert_eq!(iter.len(), expected.len() - skip);
+            assert_eq!(iter.clone().count(), expected.len() - skip);
+            let last = iter.clone().last().map(|last| last.to_vec());
+            assert_eq!(last.as_ref(), expected[*skip..].last());
+        });
+    }
+
+    #[test]
+    fn finds_nth_and_last_items() {
+        check(
+            |rng| {
+                let factors = factors(rng);
+                let len = product(&factors).len() as u64;
+                (
+                    factors,
+                    rng.range(0..=len) as usize,
+                    rng.range(0..=3) as usize,
+                )
+            },
+            |(factors, skip, n)| {
+                let expected = product(factors);
+                let mut iter = multi_cartesian_product(factors.iter().cloned());
+                for _ in 0..*skip {
+                    iter.next();
+                }
+                assert_eq!(iter.len(), expected.len() - skip);
+                assert_eq!(iter.clone().last().as_ref(), expected[*skip..].last());
+                assert_eq!(
+                    iter.nth(*n).map(<[u8]>::to_vec).as_ref(),
+                    expected[*skip..].get(*n)
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn array_product_of_nothing_has_one_empty_item() {
+        let mut iter = array_cartesian_product::<Vec<u8>, 0>([]);
+        assert_eq!(iter.len(), 1);
+        assert_eq!(iter.clone().last(), Some([]));
+        assert_eq!(iter.next(), Some(&[]));
+        assert_eq!(iter.len(), 0);
+        assert_eq!(iter.next(), None);
+    }
+
+    /// Collects the items of a product of `factors`, and checks that the product stays ended.
+    fn collect(factors: &[Vec<u8>]) -> Vec<Vec<u8>> {
+        let mut iter = multi_cartesian_product(factors.iter().cloned());
+        let mut items = vec![];
+        while let Some(item) = iter.next() {
+            items.push(item.to_vec());
         }
+        assert!(iter.next().is_none());
+        assert_eq!(iter.size_hint(), (0, Some(0)));
+        items
+    }
+
+    #[test]
+    fn product_of_nothing_has_one_empty_item() {
+        assert_eq!(collect(&[]), [vec![]]);
+        let iter = multi_cartesian_product(Vec::<Vec<u8>>::new().into_iter());
+        assert_eq!(iter.len(), 1);
+        assert_eq!(iter.last(), Some(vec![]));
+    }
+
+    #[test]
+    #[should_panic(expected = "the length overflows usize")]
+    fn len_panics_when_the_product_overflows() {
+        let iter =
+            multi_cartesian_product(std::iter::repeat_n(vec![1u8, 2], usize::BITS as usize + 1));
+        assert_eq!(iter.size_hint(), (usize::MAX, None));
+        assert!(!iter.is_empty());
+        iter.len();
+    }
+
+    #[test]
+    fn product_of_a_single_iterator_yields_its_items() {
+        assert_eq!(collect(&[vec![1, 2, 3]]), [[1], [2], [3]]);
+        assert_eq!(collect(&[vec![1]]), [[1]]);
     }
 
-    // fn last(self) -> Option<Self::Item> {
-    //     let MultiProductInner { iters, cur } = self.0?;
-    //     // Collect the last item of each iterator of the product.
-    //     if let Populated(values) = cur {
-    //         let mut count = iters.len();
-    //         let last = iters
-    //             .into_iter()
-    //             .zip(values)
-    //             .map(|(i, value)| {
-    //                 i.iter.last().unwrap_or_else(|| {
-    //                     // The iterator is empty, use its current `value`.
-    //                     count -= 1;
-    //                     value
-    //                 })
-    //             })
-    //             .collect();
-    //         if count == 0 {
-    //             // `values` was the last item.
-    //             None
-    //         } else {
-    //             Some(last)
-    //         }
-    //     } else {
-    //         iters.into_iter().map(|i| i.iter.last()).collect()
-    //     }
-    // }
-}
-
-// impl<I> std::iter::FusedIterator for MultiProduct<I>
-// where
-//     I: Iterator + Clone,
-//     I::Item: Clone,
-// {
-// }
+    #[test]
+    fn product_with_an_empty_iterator_is_empty() {
+        assert!(collect(&[vec![]]).is_empty());
+        assert!(collect(&[vec![1, 2], vec![], vec![3]]).is_empty());
+        assert!(collect(&[vec![1, 2], vec![3], vec![]]).is_empty());
+
+        let iter = multi_cartesian_product([vec![1, 2], vec![]].into_iter());
+        assert_eq!(iter.len(), 0);
+        assert_eq!(iter.clone().last(), None);
+        assert_eq!(iter.count(), 0);
+    }
+}
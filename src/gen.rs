@@ -0,0 +1,285 @@
+//! Seeded generators for random puzzle inputs.
+//!
+//! `cargo stress` uses these to compare two implementations of a day on many inputs besides the examples.
+//! Inputs are generated as a list of items (e.g. lines), so that a failing input can be shrunk by removing items.
+
+use std::ops::RangeInclusive;
+
+use crate::template::Day;
+
+/// A small, seeded pseudo-random number generator (SplitMix64). Only meant for generating test inputs.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `range`, e.g. `rng.range(1..=9)`.
+    pub fn range(&mut self, range: RangeInclusive<u64>) -> u64 {
+        let (start, end) = range.into_inner();
+        assert!(start <= end, "empty range {start}..={end}");
+        match (end - start).checked_add(1) {
+            Some(len) => start + self.next_u64() % len,
+            None => self.next_u64(),
+        }
+    }
+
+    /// Returns `true` with a probability of `percent` percent.
+    pub fn chance(&mut self, percent: u64) -> bool {
+        self.range(0..=99) < percent
+    }
+}
+
+/// A generated input, as a list of items that are joined by a separator.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Generated {
+    pub items: Vec<String>,
+    pub separator: &'static str,
+}
+
+impl Generated {
+    /// The input as it would be read from a file, with a trailing newline.
+    pub fn render(&self) -> String {
+        let mut input = self.items.join(self.separator);
+        input.push('\n');
+        input
+    }
+
+    /// The same kind of input with other items, e.g. a subset of the items while shrinking.
+    pub fn with_items(&self, items: Vec<String>) -> Generated {
+        Generated {
+            items,
+            separator: self.separator,
+        }
+    }
+}
+
+/// Generates an input with `size` items.
+pub type Generator = fn(&mut Rng, usize) -> Generated;
+
+/// Returns the generator for a day, if there is one.
+pub fn generator(day: Day) -> Option<Generator> {
+    match day.into_inner() {
+        2 => Some(reports),
+        7 => Some(equations),
+        9 => Some(disk_map),
+        11 => Some(stones),
+        _ => None,
+    }
+}
+
+/// The days that have a generator.
+pub fn days_with_generators() -> impl Iterator<Item = Day> {
+    crate::template::all_days().filter(|day| generator(*day).is_some())
+}
+
+/// Remove as many items as possible while `fails` still returns `true`, keeping at least one item.
+///
+/// Tries to remove large chunks first, then halves the chunk size whenever no chunk could be removed.
+pub fn shrink<T: Clone>(mut items: Vec<T>, mut fails: impl FnMut(&[T]) -> bool) -> Vec<T> {
+    let mut chunk = (items.len() / 2).max(1);
+    loop {
+        let mut removed = false;
+        let mut start = 0;
+        while start < items.len() {
+            let end = (start + chunk).min(items.len());
+            let candidate: Vec<T> = items[..start]
+                .iter()
+                .chain(&items[end..])
+                .cloned()
+                .collect();
+            if !candidate.is_empty() && fails(&candidate) {
+                items = candidate;
+                removed = true;
+            } else {
+                start += chunk;
+            }
+        }
+
+        if removed {
+            chunk = chunk.min((items.len() / 2).max(1));
+        } else if chunk == 1 {
+            return items;
+        } else {
+            chunk /= 2;
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Day 02: lines of levels, mostly in- or decreasing by 1 to 3 with an occasional bad step.
+pub fn reports(rng: &mut Rng, size: usize) -> Generated {
+    let items = (0..size)
+        .map(|_| {
+            let increasing = rng.chance(50);
+            let mut level = rng.range(10..=80);
+            let mut levels = vec![level];
+            for _ in 1..rng.range(5..=8) {
+                let step = if rng.chance(15) {
+                    rng.range(0..=6)
+                } else {
+                    rng.range(1..=3)
+                };
+                let increase = increasing != rng.chance(10);
+                level = if increase {
+                    level + step
+                } else {
+                    level.saturating_sub(step).max(1)
+                };
+                levels.push(level);
+            }
+            join(&levels, " ")
+        })
+        .collect();
+
+    Generated {
+        items,
+        separator: "\n",
+    }
+}
+
+/// Day 07: equations like `3267: 81 40 27`. About half of them can be solved with `+`, `*` and `||`.
+pub fn equations(rng: &mut Rng, size: usize) -> Generated {
+    let items = (0..size)
+        .map(|_| {
+            let count = rng.range(2..=6);
+            let operands: Vec<u64> = (0..count).map(|_| rng.range(1..=99)).collect();
+
+            let mut answer = operands[0];
+            for &operand in &operands[1..] {
+                answer = match rng.range(0..=2) {
+                    0 => answer + operand,
+                    1 => answer * operand,
+                    _ => answer * 10u64.pow(operand.ilog10() + 1) + operand,
+                };
+            }
+            if rng.chance(50) {
+                answer += rng.range(1..=10);
+            }
+
+            format!("{answer}: {}", join(&operands, " "))
+        })
+        .collect();
+
+    Generated {
+        items,
+        separator: "\n",
+    }
+}
+
+/// Day 09: a disk map, as pairs of a file length (1 to 9) and a free space length (0 to 9).
+pub fn disk_map(rng: &mut Rng, size: usize) -> Generated {
+    let items = (0..size)
+        .map(|_| format!("{}{}", rng.range(1..=9), rng.range(0..=9)))
+        .collect();
+
+    Generated {
+        items,
+        separator: "",
+    }
+}
+
+/// Day 11: stones with engraved numbers, including zeros and numbers with an even number of digits.
+pub fn stones(rng: &mut Rng, size: usize) -> Generated {
+    let items = (0..size)
+        .map(|_| match rng.range(0..=9) {
+            0 => 0,
+            1..=5 => rng.range(1..=9_999),
+            _ => rng.range(10_000..=9_999_999),
+        })
+        .map(|stone| stone.to_string())
+        .collect();
+
+    Generated {
+        items,
+        separator: " ",
+    }
+}
+
+fn join(values: &[u64], separator: &str) -> String {
+    values
+        .iter()
+        .map(u64::to_string)
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{days_with_generators, generator, shrink, Rng};
+    use crate::day;
+
+    #[test]
+    fn generates_the_same_inputs_for_a_seed() {
+        for day in days_with_generators() {
+            let generate = generator(day).unwrap();
+            let a = generate(&mut Rng::new(7), 10).render();
+            let b = generate(&mut Rng::new(7), 10).render();
+            let c = generate(&mut Rng::new(8), 10).render();
+            assert_eq!(a, b, "day {day}");
+            assert_ne!(a, c, "day {day}");
+        }
+    }
+
+    #[test]
+    fn keeps_numbers_in_range() {
+        let mut rng = Rng::new(1);
+        for _ in 0..1000 {
+            assert!((3..=5).contains(&rng.range(3..=5)));
+        }
+        assert_eq!(rng.range(4..=4), 4);
+        rng.range(0..=u64::MAX);
+    }
+
+    #[test]
+    fn generates_valid_inputs() {
+        let mut rng = Rng::new(2024);
+
+        let reports = generator(day!(2)).unwrap()(&mut rng, 20);
+        assert_eq!(reports.items.len(), 20);
+        for report in &reports.items {
+            let levels: Vec<u64> = report.split(' ').map(|l| l.parse().unwrap()).collect();
+            assert!((5..=8).contains(&levels.len()), "{report}");
+        }
+
+        let equations = generator(day!(7)).unwrap()(&mut rng, 20).render();
+        for equation in equations.lines() {
+            let (answer, operands) = equation.split_once(": ").unwrap();
+            answer.parse::<u64>().unwrap();
+            assert!(operands.split(' ').all(|o| o.parse::<u64>().is_ok()));
+        }
+
+        let disk_map = generator(day!(9)).unwrap()(&mut rng, 20).render();
+        assert_eq!(disk_map.trim_end().len(), 40);
+        assert!(disk_map.trim_end().chars().all(|c| c.is_ascii_digit()));
+
+        let stones = generator(day!(11)).unwrap()(&mut rng, 20).render();
+        assert_eq!(stones.split_whitespace().count(), 20);
+    }
+
+    #[test]
+    fn shrinks_to_failing_items() {
+        let items: Vec<u32> = (0..100).collect();
+        // fails whenever both 17 and 64 are present.
+        let shrunk = shrink(items, |items| items.contains(&17) && items.contains(&64));
+        assert_eq!(shrunk, [17, 64]);
+
+        assert_eq!(shrink(vec![1, 2, 3], |_| true), [3]);
+        assert_eq!(shrink(vec![1, 2, 3], |_| false), [1, 2, 3]);
+    }
+}
@@ -0,0 +1,394 @@
+//! Dense sets and maps over the cells of a grid with known bounds, to replace `HashSet<Vec2<_>>` in grid searches.
+//!
+//! Positions can be `Vec2`s of any integer type. Positions outside the grid (including negative ones) are never
+//! contained, and inserting them panics.
+
+use numeric::compound::vector::Vec2;
+
+use crate::Cardinal;
+
+/// The index of the cell at `pos` in a row-major grid of `size`, if it is inside the grid.
+fn cell_index<I: Copy + TryInto<usize>>(size: Vec2<usize>, pos: Vec2<I>) -> Option<usize> {
+    let x = (*pos.x()).try_into().ok()?;
+    let y = (*pos.y()).try_into().ok()?;
+    (x < *size.x() && y < *size.y()).then(|| y * size.x() + x)
+}
+
+fn cell_pos(size: Vec2<usize>, index: usize) -> Vec2<usize> {
+    Vec2::from_xy(index % size.x(), index / size.x())
+}
+
+fn cardinal_index(dir: Cardinal) -> usize {
+    match dir {
+        Cardinal::Up => 0,
+        Cardinal::Right => 1,
+        Cardinal::Down => 2,
+        Cardinal::Left => 3,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    fn new(bits: usize) -> BitSet {
+        BitSet {
+            words: vec![0; bits.div_ceil(64)],
+        }
+    }
+
+    fn get(&self, bit: usize) -> bool {
+        self.words[bit / 64] & (1 << (bit % 64)) != 0
+    }
+
+    /// Set `bit` to `value`, and return its previous value.
+    fn set(&mut self, bit: usize, value: bool) -> bool {
+        let word = &mut self.words[bit / 64];
+        let mask = 1 << (bit % 64);
+        let previous = *word & mask != 0;
+        if value {
+            *word |= mask;
+        } else {
+            *word &= !mask;
+        }
+        previous
+    }
+
+    fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                let bit = word.trailing_zeros() as usize;
+                (word != 0).then(|| {
+                    word &= word - 1;
+                    i * 64 + bit
+                })
+            })
+        })
+    }
+}
+
+/// A set of cells, with one bit per cell of the grid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GridSet {
+    size: Vec2<usize>,
+    bits: BitSet,
+    len: usize,
+}
+
+impl GridSet {
+    /// An empty set for a grid of `size` cells, i.e. `0..size.x()` by `0..size.y()`.
+    pub fn new(size: Vec2<usize>) -> GridSet {
+        GridSet {
+            size,
+            bits: BitSet::new(size.x() * size.y()),
+            len: 0,
+        }
+    }
+
+    pub fn size(&self) -> Vec2<usize> {
+        self.size
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn contains<I: Copy + TryInto<usize>>(&self, pos: Vec2<I>) -> bool {
+        cell_index(self.size, pos).is_some_and(|i| self.bits.get(i))
+    }
+
+    /// Add a cell, and return whether it was newly added. Panics if `pos` is outside the grid.
+    pub fn insert<I: Copy + TryInto<usize>>(&mut self, pos: Vec2<I>) -> bool {
+        let i = cell_index(self.size, pos).expect("position outside of the grid");
+        let added = !self.bits.set(i, true);
+        self.len += added as usize;
+        added
+    }
+
+    /// Remove a cell, and return whether it was in the set.
+    pub fn remove<I: Copy + TryInto<usize>>(&mut self, pos: Vec2<I>) -> bool {
+        let Some(i) = cell_index(self.size, pos) else {
+            return false;
+        };
+        let removed = self.bits.set(i, false);
+        self.len -= removed as usize;
+        removed
+    }
+
+    /// Remove all cells, keeping the allocation. Much cheaper than a new set in a loop.
+    pub fn clear(&mut self) {
+        self.bits.clear();
+        self.len = 0;
+    }
+
+    /// The cells in the set, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = Vec2<usize>> + '_ {
+        self.bits.ones().map(|i| cell_pos(self.size, i))
+    }
+}
+
+/// A set of cells together with the direction they were entered in, e.g. the states of a guard walking the grid. Packs
+/// the four [`Cardinal`]s of a cell into four bits.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DirectedGridSet {
+    size: Vec2<usize>,
+    bits: BitSet,
+    len: usize,
+}
+
+impl DirectedGridSet {
+    pub fn new(size: Vec2<usize>) -> DirectedGridSet {
+        DirectedGridSet {
+            size,
+            bits: BitSet::new(size.x() * size.y() * 4),
+            len: 0,
+        }
+    }
+
+    pub fn size(&self) -> Vec2<usize> {
+        self.size
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn contains<I: Copy + TryInto<usize>>(&self, pos: Vec2<I>, dir: Cardinal) -> bool {
+        cell_index(self.size, pos).is_some_and(|i| self.bits.get(i * 4 + cardinal_index(dir)))
+    }
+
+    /// Add a state, and return whether it was newly added. Panics if `pos` is outside the grid.
+    pub fn insert<I: Copy + TryInto<usize>>(&mut self, pos: Vec2<I>, dir: Cardinal) -> bool {
+        let i = cell_index(self.size, pos).expect("position outside of the grid");
+        let added = !self.bits.set(i * 4 + cardinal_index(dir), true);
+        self.len += added as usize;
+        added
+    }
+
+    /// The directions `pos` is in the set with.
+    pub fn directions<I: Copy + TryInto<usize>>(
+        &self,
+        pos: Vec2<I>,
+    ) -> impl Iterator<Item = Cardinal> + '_ {
+        let cell = cell_index(self.size, pos);
+        Cardinal::all()
+            .into_iter()
+            .filter(move |&dir| cell.is_some_and(|i| self.bits.get(i * 4 + cardinal_index(dir))))
+    }
+
+    pub fn clear(&mut self) {
+        self.bits.clear();
+        self.len = 0;
+    }
+
+    /// The cells in the set with at least one direction, row by row.
+    pub fn cells(&self) -> GridSet {
+        let mut cells = GridSet::new(self.size);
+        for i in self.bits.ones() {
+            cells.insert(cell_pos(self.size, i / 4));
+        }
+        cells
+    }
+}
+
+/// A map from cells to values, backed by a flat array with a slot per cell.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GridMap<T> {
+    size: Vec2<usize>,
+    cells: Vec<Option<T>>,
+    len: usize,
+}
+
+impl<T> GridMap<T> {
+    pub fn new(size: Vec2<usize>) -> GridMap<T> {
+        GridMap {
+            size,
+            cells: std::iter::repeat_with(|| None)
+                .take(size.x() * size.y())
+                .collect(),
+            len: 0,
+        }
+    }
+
+    pub fn size(&self) -> Vec2<usize> {
+        self.size
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn contains_key<I: Copy + TryInto<usize>>(&self, pos: Vec2<I>) -> bool {
+        self.get(pos).is_some()
+    }
+
+    pub fn get<I: Copy + TryInto<usize>>(&self, pos: Vec2<I>) -> Option<&T> {
+        self.cells[cell_index(self.size, pos)?].as_ref()
+    }
+
+    pub fn get_mut<I: Copy + TryInto<usize>>(&mut self, pos: Vec2<I>) -> Option<&mut T> {
+        self.cells[cell_index(self.size, pos)?].as_mut()
+    }
+
+    /// Set the value of a cell, and return its previous value. Panics if `pos` is outside the grid.
+    pub fn insert<I: Copy + TryInto<usize>>(&mut self, pos: Vec2<I>, value: T) -> Option<T> {
+        let i = cell_index(self.size, pos).expect("position outside of the grid");
+        let previous = self.cells[i].replace(value);
+        self.len += previous.is_none() as usize;
+        previous
+    }
+
+    pub fn remove<I: Copy + TryInto<usize>>(&mut self, pos: Vec2<I>) -> Option<T> {
+        let previous = self.cells[cell_index(self.size, pos)?].take();
+        self.len -= previous.is_some() as usize;
+        previous
+    }
+
+    /// The value of a cell, inserting `default()` first if it has none. Panics if `pos` is outside the grid.
+    pub fn get_or_insert_with<I: Copy + TryInto<usize>>(
+        &mut self,
+        pos: Vec2<I>,
+        default: impl FnOnce() -> T,
+    ) -> &mut T {
+        let i = cell_index(self.size, pos).expect("position outside of the grid");
+        let cell = &mut self.cells[i];
+        if cell.is_none() {
+            self.len += 1;
+        }
+        cell.get_or_insert_with(default)
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.fill_with(|| None);
+        self.len = 0;
+    }
+
+    /// The cells with a value and their values, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Vec2<usize>, &T)> + '_ {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter_map(|(i, value)| Some((cell_pos(self.size, i), value.as_ref()?)))
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use numeric::compound::vector::Vec2;
+
+    use super::{DirectedGridSet, GridMap, GridSet};
+    use crate::gen::Rng;
+    use crate::prop::{check, vec_of};
+    use crate::Cardinal;
+
+    /// A grid size and operations on it, some of them outside the grid.
+    fn operations(rng: &mut Rng) -> (Vec2<usize>, Vec<(bool, Vec2<i64>)>) {
+        let size = Vec2::from_xy(rng.range(1..=20) as usize, rng.range(1..=20) as usize);
+        let ops = vec_of(rng, 0..=200, |rng| {
+            let pos = Vec2::from_xy(rng.range(0..=22) as i64 - 1, rng.range(0..=22) as i64 - 1);
+            (rng.chance(70), pos)
+        });
+        (size, ops)
+    }
+
+    fn inside(size: Vec2<usize>, pos: Vec2<i64>) -> bool {
+        (0..*size.x() as i64).contains(pos.x()) && (0..*size.y() as i64).contains(pos.y())
+    }
+
+    #[test]
+    fn grid_set_behaves_like_a_hash_set() {
+        check(operations, |(size, ops)| {
+            let mut set = GridSet::new(*size);
+            let mut expected = HashSet::new();
+            for &(insert, pos) in ops {
+                if insert && inside(*size, pos) {
+                    assert_eq!(set.insert(pos), expected.insert(pos));
+                } else if !insert {
+                    assert_eq!(set.remove(pos), expected.remove(&pos));
+                }
+                assert_eq!(set.contains(pos), expected.contains(&pos));
+                assert_eq!(set.len(), expected.len());
+            }
+            let cells: HashSet<_> = set
+                .iter()
+                .map(|pos| Vec2::from_xy(*pos.x() as i64, *pos.y() as i64))
+                .collect();
+            assert_eq!(cells, expected);
+
+            set.clear();
+            assert!(set.is_empty() && set.iter().next().is_none());
+        });
+    }
+
+    #[test]
+    fn grid_map_behaves_like_a_hash_map() {
+        check(operations, |(size, ops)| {
+            let mut map = GridMap::new(*size);
+            let mut expected = HashMap::new();
+            for (i, &(insert, pos)) in ops.iter().enumerate() {
+                if insert && inside(*size, pos) {
+                    assert_eq!(map.insert(pos, i), expected.insert(pos, i));
+                    *map.get_or_insert_with(pos, || 0) += 1;
+                    *expected.entry(pos).or_insert(0) += 1;
+                } else if !insert {
+                    assert_eq!(map.remove(pos), expected.remove(&pos));
+                }
+                assert_eq!(map.get(pos), expected.get(&pos));
+                assert_eq!(map.len(), expected.len());
+            }
+            assert_eq!(map.iter().count(), expected.len());
+        });
+    }
+
+    #[test]
+    fn directed_set_packs_directions() {
+        let mut set = DirectedGridSet::new(Vec2::from_xy(3, 2));
+        assert!(set.insert(Vec2::from_xy(2u32, 1), Cardinal::Left));
+        assert!(set.insert(Vec2::from_xy(2u32, 1), Cardinal::Up));
+        assert!(!set.insert(Vec2::from_xy(2u32, 1), Cardinal::Up));
+        assert!(set.insert(Vec2::from_xy(0u32, 0), Cardinal::Up));
+
+        assert!(set.contains(Vec2::from_xy(2u32, 1), Cardinal::Left));
+        assert!(!set.contains(Vec2::from_xy(2u32, 1), Cardinal::Down));
+        assert!(!set.contains(Vec2::from_xy(1u32, 1), Cardinal::Left));
+        assert!(!set.contains(Vec2::from_xy(-1i32, 1), Cardinal::Left));
+        assert_eq!(set.len(), 3);
+        assert_eq!(
+            set.directions(Vec2::from_xy(2u32, 1)).collect::<Vec<_>>(),
+            [Cardinal::Up, Cardinal::Left]
+        );
+        assert_eq!(
+            set.cells().iter().collect::<Vec<_>>(),
+            [Vec2::from_xy(0, 0), Vec2::from_xy(2, 1)]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "position outside of the grid")]
+    fn inserting_outside_the_grid_panics() {
+        GridSet::new(Vec2::from_xy(3, 3)).insert(Vec2::from_xy(3usize, 0));
+    }
+}
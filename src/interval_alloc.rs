@@ -0,0 +1,296 @@
+//! An allocator of ranges of positions, like the blocks of day 09's disk.
+//!
+//! The free ranges are kept twice: in a map from start to end, which merges ranges that touch when one is freed, and in
+//! buckets by length, each a min-heap of starts. A query only looks at the top of the buckets that are long enough, so
+//! it doesn't scan the free ranges. Entries of ranges that were allocated or merged since are left in the heaps and
+//! skipped when they reach the top.
+//!
+//! Ranges at least [`LARGE`] long share the last bucket. Queries for such lengths scan the free ranges instead.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap};
+use std::ops::Range;
+
+/// The length from which free ranges share a bucket.
+pub const LARGE: usize = 16;
+
+#[derive(Clone, Debug)]
+pub struct IntervalAllocator {
+    /// The start and end of each free range. Ranges never touch, touching ones are merged.
+    free: BTreeMap<usize, usize>,
+    /// The starts of free ranges of each length, and of those at least [`LARGE`] long in the last bucket.
+    buckets: [BinaryHeap<Reverse<usize>>; LARGE + 1],
+    free_len: usize,
+}
+
+fn bucket(len: usize) -> usize {
+    len.min(LARGE)
+}
+
+impl Default for IntervalAllocator {
+    fn default() -> Self {
+        IntervalAllocator::new()
+    }
+}
+
+impl IntervalAllocator {
+    /// An allocator without free space.
+    pub fn new() -> IntervalAllocator {
+        IntervalAllocator {
+            free: BTreeMap::new(),
+            buckets: std::array::from_fn(|_| BinaryHeap::new()),
+            free_len: 0,
+        }
+    }
+
+    /// The total length of the free ranges.
+    pub fn free_len(&self) -> usize {
+        self.free_len
+    }
+
+    /// The free ranges, from left to right.
+    pub fn ranges(&self) -> impl Iterator<Item = Range<usize>> + '_ {
+        self.free.iter().map(|(&start, &end)| start..end)
+    }
+
+    /// The free range containing `pos`, if it is free.
+    pub fn range_at(&self, pos: usize) -> Option<Range<usize>> {
+        let (&start, &end) = self.free.range(..=pos).next_back()?;
+        (pos < end).then_some(start..end)
+    }
+
+    pub fn is_free(&self, pos: usize) -> bool {
+        self.range_at(pos).is_some()
+    }
+
+    fn insert(&mut self, range: Range<usize>) {
+        self.buckets[bucket(range.len())].push(Reverse(range.start));
+        self.free.insert(range.start, range.end);
+    }
+
+    /// Make `range` free, merging it with the free ranges it touches. Panics if part of it is already free.
+    pub fn free(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+        let overlaps = self
+            .free
+            .range(..range.end)
+            .next_back()
+            .is_some_and(|(_, &end)| end > range.start);
+        assert!(!overlaps, "{range:?} is already partly free");
+        self.free_len += range.len();
+
+        let mut merged = range.clone();
+        if let Some((&start, &end)) = self.free.range(..range.start).next_back() {
+            if end == range.start {
+                self.free.remove(&start);
+                merged.start = start;
+            }
+        }
+        if let Some(end) = self.free.remove(&range.end) {
+            merged.end = end;
+        }
+        self.insert(merged);
+    }
+
+    /// Mark `range` as used. Panics unless it is inside a single free range.
+    pub fn allocate(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+        let free = self
+            .range_at(range.start)
+            .filter(|free| range.end <= free.end)
+            .unwrap_or_else(|| panic!("{range:?} isn't free"));
+        self.free.remove(&free.start);
+        self.free_len -= range.len();
+        for rest in [free.start..range.start, range.end..free.end] {
+            if !rest.is_empty() {
+                self.insert(rest);
+            }
+        }
+    }
+
+    /// The leftmost free range in a bucket, dropping the entries of ranges that are gone or have a different length.
+    fn peek(&mut self, bucket: usize) -> Option<Range<usize>> {
+        let heap = &mut self.buckets[bucket];
+        while let Some(&Reverse(start)) = heap.peek() {
+            match self.free.get(&start) {
+                Some(&end) if self::bucket(end - start) == bucket => return Some(start..end),
+                _ => {
+                    heap.pop();
+                }
+            }
+        }
+        None
+    }
+
+    /// The leftmost free range at least `len` long, for requests that only fit in the last bucket.
+    fn scan(&self, len: usize) -> Option<Range<usize>> {
+        self.ranges().find(|range| range.len() >= len)
+    }
+
+    /// A best fit by bucket: the leftmost free range of the smallest bucket that fits `len`. Unlike
+    /// [`leftmost_fit`](IntervalAllocator::leftmost_fit), it keeps long ranges for long requests.
+    pub fn smallest_fit(&mut self, len: usize) -> Option<Range<usize>> {
+        if len >= LARGE {
+            return self.scan(len);
+        }
+        (bucket(len.max(1))..=LARGE).find_map(|bucket| self.peek(bucket))
+    }
+
+    /// The leftmost free range at least `len` long.
+    pub fn leftmost_fit(&mut self, len: usize) -> Option<Range<usize>> {
+        if len >= LARGE {
+            return self.scan(len);
+        }
+        (bucket(len.max(1))..=LARGE)
+            .filter_map(|bucket| self.peek(bucket))
+            .min_by_key(|range| range.start)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::ops::Range;
+
+    use super::{IntervalAllocator, LARGE};
+    use crate::gen::Rng;
+    use crate::prop::{check, vec_of};
+
+    fn allocator(free: &[Range<usize>]) -> IntervalAllocator {
+        let mut allocator = IntervalAllocator::new();
+        for range in free {
+            allocator.free(range.clone());
+        }
+        allocator
+    }
+
+    #[test]
+    fn merges_touching_ranges() {
+        let mut allocator = allocator(&[0..2, 5..7, 2..3, 4..5]);
+        assert_eq!(allocator.ranges().collect::<Vec<_>>(), [0..3, 4..7]);
+        allocator.free(3..4);
+        assert_eq!(allocator.ranges().collect::<Vec<_>>(), [0..7]);
+        assert_eq!(allocator.free_len(), 7);
+        assert_eq!(allocator.leftmost_fit(7), Some(0..7));
+    }
+
+    #[test]
+    #[should_panic(expected = "is already partly free")]
+    fn panics_on_double_free() {
+        allocator(&[2..5, 4..6]);
+    }
+
+    #[test]
+    fn splits_allocated_ranges() {
+        let mut allocator = allocator(&[0..10]);
+        allocator.allocate(3..5);
+        assert_eq!(allocator.ranges().collect::<Vec<_>>(), [0..3, 5..10]);
+        assert!(!allocator.is_free(4));
+        assert_eq!(allocator.range_at(7), Some(5..10));
+        assert_eq!(allocator.leftmost_fit(4), Some(5..10));
+        assert_eq!(allocator.leftmost_fit(6), None);
+        assert_eq!(allocator.free_len(), 8);
+    }
+
+    #[test]
+    #[should_panic(expected = "isn't free")]
+    fn panics_on_allocating_used_space() {
+        allocator(&[0..3, 4..6]).allocate(2..5);
+    }
+
+    #[test]
+    fn fits_by_position_or_by_bucket() {
+        let mut allocator = allocator(&[0..5, 10..12, 20..23]);
+        assert_eq!(allocator.leftmost_fit(2), Some(0..5));
+        assert_eq!(allocator.smallest_fit(2), Some(10..12));
+        assert_eq!(allocator.smallest_fit(3), Some(20..23));
+        assert_eq!(allocator.smallest_fit(6), None);
+
+        allocator.allocate(10..12);
+        assert_eq!(allocator.smallest_fit(2), Some(20..23));
+    }
+
+    #[test]
+    fn fits_large_ranges() {
+        let mut allocator = allocator(&[0..LARGE, 100..100 + 2 * LARGE]);
+        assert_eq!(allocator.leftmost_fit(LARGE - 1), Some(0..LARGE));
+        assert_eq!(
+            allocator.leftmost_fit(LARGE + 1),
+            Some(100..100 + 2 * LARGE)
+        );
+        assert_eq!(allocator.smallest_fit(2 * LARGE), Some(100..100 + 2 * LARGE));
+        assert_eq!(allocator.smallest_fit(2 * LARGE + 1), None);
+    }
+
+    #[derive(Debug)]
+    enum Action {
+        Free(Range<usize>),
+        Allocate(usize),
+    }
+
+    fn actions(rng: &mut Rng) -> Vec<Action> {
+        vec_of(rng, 0..=60, |rng| {
+            let start = rng.range(0..=80) as usize;
+            let len = rng.range(1..=2 * LARGE as u64) as usize;
+            if rng.chance(50) {
+                Action::Free(start..start + len)
+            } else {
+                Action::Allocate(len)
+            }
+        })
+    }
+
+    #[test]
+    fn agrees_with_a_bitmap() {
+        check(actions, |actions| {
+            let mut allocator = IntervalAllocator::new();
+            let mut free = vec![false; 80 + 2 * LARGE];
+            let runs = |free: &[bool]| {
+                let mut runs = Vec::new();
+                let mut start = None;
+                for (i, &is_free) in free.iter().chain([&false]).enumerate() {
+                    match (start, is_free) {
+                        (None, true) => start = Some(i),
+                        (Some(s), false) => {
+                            runs.push(s..i);
+                            start = None;
+                        }
+                        _ => (),
+                    }
+                }
+                runs
+            };
+
+            for action in actions {
+                match action {
+                    Action::Free(range) => {
+                        if free[range.clone()].iter().any(|&is_free| is_free) {
+                            continue;
+                        }
+                        free[range.clone()].fill(true);
+                        allocator.free(range.clone());
+                    }
+                    &Action::Allocate(len) => {
+                        let fits = runs(&free).into_iter().filter(|run| run.len() >= len);
+                        let leftmost = fits.clone().next();
+                        assert_eq!(allocator.leftmost_fit(len), leftmost);
+                        let smallest = fits.min_by_key(|run| (run.len().min(LARGE), run.start));
+                        assert_eq!(allocator.smallest_fit(len), smallest);
+
+                        if let Some(run) = leftmost {
+                            free[run.start..run.start + len].fill(false);
+                            allocator.allocate(run.start..run.start + len);
+                        }
+                    }
+                }
+                assert_eq!(allocator.ranges().collect::<Vec<_>>(), runs(&free));
+                assert_eq!(allocator.free_len(), free.iter().filter(|&&f| f).count());
+            }
+        });
+    }
+}
@@ -1,13 +1,22 @@
 use chumsky::{text, Parser};
-use numeric::compound::vector::Vec2;
+use numeric::compound::vector::{Vec2, Vec3};
 use numeric::traits::class::Integral;
 use numeric::traits::ops::checked::{CheckedAdd, CheckedSub};
 use std::str::FromStr;
 use chumsky::prelude::just;
 
+pub mod bounds;
+pub mod detect;
+pub mod expr_search;
 pub mod fast_cartesian;
+pub mod gen;
+pub mod grid;
+pub mod interval_alloc;
+#[cfg(feature = "test_lib")]
+pub mod prop;
 mod size_hint;
 pub mod template;
+pub mod viz;
 
 // Use this file to add helper functions and additional modules.
 
@@ -31,6 +40,24 @@ pub fn int_i64<'a>() -> Parser!['a, i64] {
         .map(|(neg, val)| if neg.is_some() { -val } else { val })
 }
 
+/// A [`Cardinal`] written as an arrow, one of `^v<>`.
+pub fn arrow<'a>() -> Parser!['a, Cardinal] {
+    chumsky::prelude::any().try_map(|c, span| {
+        Cardinal::from_arrow(c).ok_or_else(|| {
+            chumsky::error::Rich::custom(span, format!("expected one of ^v<>, found {c:?}"))
+        })
+    })
+}
+
+/// A [`Cardinal`] written as a compass letter, one of `NSEW`.
+pub fn compass<'a>() -> Parser!['a, Cardinal] {
+    chumsky::prelude::any().try_map(|c, span| {
+        Cardinal::from_compass(c).ok_or_else(|| {
+            chumsky::error::Rich::custom(span, format!("expected one of NSEW, found {c:?}"))
+        })
+    })
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Diagonal {
     UpLeft,
@@ -131,29 +158,518 @@ impl Cardinal {
             Cardinal::Left => Vec2::from_xy(-1, 0),
         }
     }
+
+    /// Parse an arrow, one of `^v<>`.
+    pub const fn from_arrow(c: char) -> Option<Cardinal> {
+        match c {
+            '^' => Some(Cardinal::Up),
+            'v' => Some(Cardinal::Down),
+            '>' => Some(Cardinal::Right),
+            '<' => Some(Cardinal::Left),
+            _ => None,
+        }
+    }
+
+    /// Parse a compass letter, one of `NSEW`. North is [`Cardinal::Up`].
+    pub const fn from_compass(c: char) -> Option<Cardinal> {
+        match c {
+            'N' => Some(Cardinal::Up),
+            'S' => Some(Cardinal::Down),
+            'E' => Some(Cardinal::Right),
+            'W' => Some(Cardinal::Left),
+            _ => None,
+        }
+    }
+}
+
+/// One of the eight directions to a neighbouring cell, i.e. a [`Cardinal`] or a [`Diagonal`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Direction8 {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
 }
 
-pub trait VecExt {
+impl Direction8 {
+    const ALL: [Direction8; 8] = [
+        Direction8::Up,
+        Direction8::UpRight,
+        Direction8::Right,
+        Direction8::DownRight,
+        Direction8::Down,
+        Direction8::DownLeft,
+        Direction8::Left,
+        Direction8::UpLeft,
+    ];
+
+    /// All directions in clockwise order, starting with [`Direction8::Up`].
+    pub fn all() -> [Direction8; 8] {
+        Direction8::ALL
+    }
+
+    /// Rotate clockwise by `eighths` of a full turn.
+    const fn rotate(self, eighths: usize) -> Direction8 {
+        Direction8::ALL[(self as usize + eighths) % 8]
+    }
+
+    /// Rotate clockwise by 45°.
+    pub const fn rotate_right(self) -> Direction8 {
+        self.rotate(1)
+    }
+
+    /// Rotate counterclockwise by 45°.
+    pub const fn rotate_left(self) -> Direction8 {
+        self.rotate(7)
+    }
+
+    pub const fn rotate_right_90(self) -> Direction8 {
+        self.rotate(2)
+    }
+
+    pub const fn rotate_left_90(self) -> Direction8 {
+        self.rotate(6)
+    }
+
+    pub const fn opposite(self) -> Direction8 {
+        self.rotate(4)
+    }
+
+    pub const fn as_cardinal(self) -> Option<Cardinal> {
+        match self {
+            Direction8::Up => Some(Cardinal::Up),
+            Direction8::Right => Some(Cardinal::Right),
+            Direction8::Down => Some(Cardinal::Down),
+            Direction8::Left => Some(Cardinal::Left),
+            _ => None,
+        }
+    }
+
+    pub const fn as_diagonal(self) -> Option<Diagonal> {
+        match self {
+            Direction8::UpRight => Some(Diagonal::UpRight),
+            Direction8::DownRight => Some(Diagonal::DownRight),
+            Direction8::DownLeft => Some(Diagonal::DownLeft),
+            Direction8::UpLeft => Some(Diagonal::UpLeft),
+            _ => None,
+        }
+    }
+
+    pub fn unit(self) -> Vec2<isize> {
+        match self {
+            Direction8::Up => Vec2::from_xy(0, 1),
+            Direction8::UpRight => Vec2::from_xy(1, 1),
+            Direction8::Right => Vec2::from_xy(1, 0),
+            Direction8::DownRight => Vec2::from_xy(1, -1),
+            Direction8::Down => Vec2::from_xy(0, -1),
+            Direction8::DownLeft => Vec2::from_xy(-1, -1),
+            Direction8::Left => Vec2::from_xy(-1, 0),
+            Direction8::UpLeft => Vec2::from_xy(-1, 1),
+        }
+    }
+
+    pub fn try_move<I: Integral + CheckedAdd<Output = I> + CheckedSub<Output = I>>(
+        self,
+        pos: Vec2<I>,
+    ) -> Option<Vec2<I>> {
+        match self {
+            Direction8::Up => Cardinal::Up.try_move(pos),
+            Direction8::UpRight => Diagonal::UpRight.try_move(pos),
+            Direction8::Right => Cardinal::Right.try_move(pos),
+            Direction8::DownRight => Diagonal::DownRight.try_move(pos),
+            Direction8::Down => Cardinal::Down.try_move(pos),
+            Direction8::DownLeft => Diagonal::DownLeft.try_move(pos),
+            Direction8::Left => Cardinal::Left.try_move(pos),
+            Direction8::UpLeft => Diagonal::UpLeft.try_move(pos),
+        }
+    }
+}
+
+impl From<Cardinal> for Direction8 {
+    fn from(dir: Cardinal) -> Direction8 {
+        match dir {
+            Cardinal::Up => Direction8::Up,
+            Cardinal::Right => Direction8::Right,
+            Cardinal::Down => Direction8::Down,
+            Cardinal::Left => Direction8::Left,
+        }
+    }
+}
+
+impl From<Diagonal> for Direction8 {
+    fn from(diag: Diagonal) -> Direction8 {
+        match diag {
+            Diagonal::UpRight => Direction8::UpRight,
+            Diagonal::DownRight => Direction8::DownRight,
+            Diagonal::DownLeft => Direction8::DownLeft,
+            Diagonal::UpLeft => Direction8::UpLeft,
+        }
+    }
+}
+
+/// Helpers for vectors of any primitive integer.
+///
+/// Positions use math coordinates: `y` grows upwards, so [`Cardinal::Up`] is `+y`. Puzzle inputs are written in
+/// screen coordinates, where `y` grows downwards from the first line; [`VecExt::screen_to_math`] and
+/// [`VecExt::math_to_screen`] convert between the two.
+pub trait VecExt: Sized {
+    type Scalar;
     type Signed;
+    /// The unsigned type distances are measured in.
+    type Distance;
 
     fn add_signed(self, rhs: Self::Signed) -> Self;
     fn sub_signed(self, rhs: Self::Signed) -> Self;
+
+    /// The sum of the distances along each axis. Saturates instead of overflowing.
+    fn manhattan(self, rhs: Self) -> Self::Distance;
+    /// The largest distance along an axis, i.e. the number of king moves between the two positions.
+    fn chebyshev(self, rhs: Self) -> Self::Distance;
+
+    /// Convert a position on line `y` of an input with `rows` lines to math coordinates.
+    fn screen_to_math(self, rows: Self::Scalar) -> Self;
+
+    /// Convert a position in math coordinates to line `y` of an input with `rows` lines.
+    fn math_to_screen(self, rows: Self::Scalar) -> Self {
+        // flipping `y` is its own inverse.
+        self.screen_to_math(rows)
+    }
+}
+
+macro_rules! impl_vec_ext {
+    ($($ty:ty, $signed:ty, $distance:ty, $add_signed:ident, $sub_signed:ident;)*) => {$(
+        impl VecExt for Vec2<$ty> {
+            type Scalar = $ty;
+            type Signed = Vec2<$signed>;
+            type Distance = $distance;
+
+            fn add_signed(self, rhs: Self::Signed) -> Self {
+                Vec2::from_xy(
+                    self.x().$add_signed(*rhs.x()),
+                    self.y().$add_signed(*rhs.y()),
+                )
+            }
+
+            fn sub_signed(self, rhs: Self::Signed) -> Self {
+                Vec2::from_xy(
+                    self.x().$sub_signed(*rhs.x()),
+                    self.y().$sub_signed(*rhs.y()),
+                )
+            }
+
+            fn manhattan(self, rhs: Self) -> $distance {
+                self.x().abs_diff(*rhs.x()).saturating_add(self.y().abs_diff(*rhs.y()))
+            }
+
+            fn chebyshev(self, rhs: Self) -> $distance {
+                self.x().abs_diff(*rhs.x()).max(self.y().abs_diff(*rhs.y()))
+            }
+
+            fn screen_to_math(self, rows: $ty) -> Self {
+                Vec2::from_xy(*self.x(), rows - 1 - *self.y())
+            }
+        }
+
+        impl VecExt for Vec3<$ty> {
+            type Scalar = $ty;
+            type Signed = Vec3<$signed>;
+            type Distance = $distance;
+
+            fn add_signed(self, rhs: Self::Signed) -> Self {
+                Vec3::new([
+                    self.x().$add_signed(*rhs.x()),
+                    self.y().$add_signed(*rhs.y()),
+                    self.z().$add_signed(*rhs.z()),
+                ])
+            }
+
+            fn sub_signed(self, rhs: Self::Signed) -> Self {
+                Vec3::new([
+                    self.x().$sub_signed(*rhs.x()),
+                    self.y().$sub_signed(*rhs.y()),
+                    self.z().$sub_signed(*rhs.z()),
+                ])
+            }
+
+            fn manhattan(self, rhs: Self) -> $distance {
+                self.x().abs_diff(*rhs.x())
+                    .saturating_add(self.y().abs_diff(*rhs.y()))
+                    .saturating_add(self.z().abs_diff(*rhs.z()))
+            }
+
+            fn chebyshev(self, rhs: Self) -> $distance {
+                self.x().abs_diff(*rhs.x())
+                    .max(self.y().abs_diff(*rhs.y()))
+                    .max(self.z().abs_diff(*rhs.z()))
+            }
+
+            fn screen_to_math(self, rows: $ty) -> Self {
+                Vec3::new([*self.x(), rows - 1 - *self.y(), *self.z()])
+            }
+        }
+    )*};
+}
+
+impl_vec_ext! {
+    u8, i8, u8, saturating_add_signed, saturating_sub_signed;
+    u16, i16, u16, saturating_add_signed, saturating_sub_signed;
+    u32, i32, u32, saturating_add_signed, saturating_sub_signed;
+    u64, i64, u64, saturating_add_signed, saturating_sub_signed;
+    u128, i128, u128, saturating_add_signed, saturating_sub_signed;
+    usize, isize, usize, saturating_add_signed, saturating_sub_signed;
+    i8, i8, u8, saturating_add, saturating_sub;
+    i16, i16, u16, saturating_add, saturating_sub;
+    i32, i32, u32, saturating_add, saturating_sub;
+    i64, i64, u64, saturating_add, saturating_sub;
+    i128, i128, u128, saturating_add, saturating_sub;
+    isize, isize, usize, saturating_add, saturating_sub;
+}
+
+/// The neighbours of a position on a grid, in clockwise order starting with [`Cardinal::Up`]. Neighbours that can't be
+/// represented, e.g. left of `x = 0` for unsigned positions, are skipped.
+pub trait Neighbours: Sized {
+    fn neighbours4(self) -> impl Iterator<Item = Self>;
+    /// The cardinal and the diagonal neighbours.
+    fn neighbours8(self) -> impl Iterator<Item = Self>;
 }
 
-impl VecExt for Vec2<usize> {
-    type Signed = Vec2<isize>;
+impl<I> Neighbours for Vec2<I>
+where
+    I: Integral + CheckedAdd<Output = I> + CheckedSub<Output = I> + Copy,
+{
+    fn neighbours4(self) -> impl Iterator<Item = Self> {
+        Cardinal::all().into_iter().filter_map(move |dir| dir.try_move(self))
+    }
+
+    fn neighbours8(self) -> impl Iterator<Item = Self> {
+        Direction8::all().into_iter().filter_map(move |dir| dir.try_move(self))
+    }
+}
 
-    fn add_signed(self, rhs: Self::Signed) -> Self {
-        Vec2::from_xy(
-            self.x().saturating_add_signed(*rhs.x()),
-            self.y().saturating_add_signed(*rhs.y()),
-        )
+#[cfg(feature = "test_lib")]
+mod tests {
+    use numeric::compound::vector::{Vec2, Vec3};
+
+    use crate::prop::{check, i64_with_edges, usize_with_edges};
+    use crate::{Cardinal, Diagonal, Direction8, Neighbours, VecExt};
+
+    fn usize_pos(rng: &mut crate::gen::Rng) -> Vec2<usize> {
+        Vec2::from_xy(usize_with_edges(rng), usize_with_edges(rng))
+    }
+
+    fn i64_pos(rng: &mut crate::gen::Rng) -> Vec2<i64> {
+        Vec2::from_xy(i64_with_edges(rng), i64_with_edges(rng))
+    }
+
+    #[test]
+    fn rotating_four_times_is_identity() {
+        for dir in Cardinal::all() {
+            assert_eq!(dir.rotate_right().rotate_right().rotate_right().rotate_right(), dir);
+        }
+        for dir in Diagonal::all() {
+            assert_eq!(dir.rotate_right().rotate_right().rotate_right().rotate_right(), dir);
+        }
     }
 
-    fn sub_signed(self, rhs: Self::Signed) -> Self {
-        Vec2::from_xy(
-            self.x().saturating_sub_signed(*rhs.x()),
-            self.y().saturating_sub_signed(*rhs.y()),
-        )
+    #[test]
+    fn all_is_in_clockwise_order() {
+        let cardinals = Cardinal::all();
+        let diagonals = Diagonal::all();
+        for i in 0..4 {
+            assert_eq!(cardinals[i].rotate_right(), cardinals[(i + 1) % 4]);
+            assert_eq!(diagonals[i].rotate_right(), diagonals[(i + 1) % 4]);
+        }
+    }
+
+    #[test]
+    fn cardinal_moves_round_trip() {
+        check(usize_pos, |&pos| {
+            for dir in Cardinal::all() {
+                let opposite = dir.rotate_right().rotate_right();
+                if let Some(moved) = dir.try_move(pos) {
+                    assert_eq!(opposite.try_move(moved), Some(pos), "{dir:?}");
+                }
+            }
+        });
+        check(i64_pos, |&pos| {
+            for dir in Cardinal::all() {
+                let opposite = dir.rotate_right().rotate_right();
+                if let Some(moved) = dir.try_move(pos) {
+                    assert_eq!(opposite.try_move(moved), Some(pos), "{dir:?}");
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn diagonal_moves_round_trip() {
+        check(usize_pos, |&pos| {
+            for dir in Diagonal::all() {
+                let opposite = dir.rotate_right().rotate_right();
+                if let Some(moved) = dir.try_move(pos) {
+                    assert_eq!(opposite.try_move(moved), Some(pos), "{dir:?}");
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn diagonal_moves_are_two_cardinal_moves() {
+        check(usize_pos, |&pos| {
+            for dir in Diagonal::all() {
+                let [a, b] = dir.cardinals();
+                let two_moves = a.try_move(pos).and_then(|pos| b.try_move(pos));
+                assert_eq!(dir.try_move(pos), two_moves, "{dir:?}");
+            }
+        });
+    }
+
+    #[test]
+    fn moves_agree_with_units() {
+        check(usize_pos, |&pos| {
+            for dir in Cardinal::all() {
+                if let Some(moved) = dir.try_move(pos) {
+                    assert_eq!(pos.add_signed(dir.unit()), moved, "{dir:?}");
+                    assert_eq!(moved.sub_signed(dir.unit()), pos, "{dir:?}");
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn distances_are_metrics() {
+        check(
+            |rng| (i64_pos(rng), i64_pos(rng)),
+            |&(a, b)| {
+                let dx = (*a.x() as i128 - *b.x() as i128).unsigned_abs();
+                let dy = (*a.y() as i128 - *b.y() as i128).unsigned_abs();
+                assert_eq!(a.manhattan(b) as u128, (dx + dy).min(u64::MAX as u128));
+                assert_eq!(a.chebyshev(b) as u128, dx.max(dy));
+                assert_eq!(a.manhattan(b), b.manhattan(a));
+                assert_eq!(a.chebyshev(b), b.chebyshev(a));
+                assert_eq!(a.manhattan(a), 0);
+            },
+        );
+        let a = Vec3::new([1u8, 5, 2]);
+        let b = Vec3::new([4u8, 0, 2]);
+        assert_eq!(a.manhattan(b), 8);
+        assert_eq!(a.chebyshev(b), 5);
+    }
+
+    #[test]
+    fn converts_between_screen_and_math_coordinates() {
+        let rows = 10;
+        // the first line of the input is the top row.
+        assert_eq!(Vec2::from_xy(3usize, 0).screen_to_math(rows), Vec2::from_xy(3, 9));
+        assert_eq!(Vec2::from_xy(3usize, 9).math_to_screen(rows), Vec2::from_xy(3, 0));
+        check(
+            |rng| Vec2::from_xy(rng.range(0..=100) as i32, rng.range(0..=9) as i32),
+            |&pos| {
+                let math = pos.screen_to_math(10);
+                assert_eq!(math.math_to_screen(10), pos);
+                // a line further down the input is lower in math coordinates.
+                let below = Vec2::from_xy(*pos.x(), pos.y() + 1).screen_to_math(10);
+                assert_eq!(Cardinal::Down.try_move(math), Some(below));
+            },
+        );
+    }
+
+    #[test]
+    fn neighbours_are_one_move_away() {
+        check(usize_pos, |&pos| {
+            let four: Vec<_> = pos.neighbours4().collect();
+            let eight: Vec<_> = pos.neighbours8().collect();
+            assert!(four.iter().all(|n| n.manhattan(pos) == 1));
+            assert!(eight.iter().all(|n| n.chebyshev(pos) == 1));
+            assert!(four.iter().all(|n| eight.contains(n)));
+
+            let at_edge = |v: usize| v == 0 || v == usize::MAX;
+            let edges = at_edge(*pos.x()) as usize + at_edge(*pos.y()) as usize;
+            if edges == 0 {
+                assert_eq!((four.len(), eight.len()), (4, 8));
+            }
+        });
+        let neighbours: Vec<_> = Vec2::from_xy(0usize, 0).neighbours8().collect();
+        assert_eq!(
+            neighbours,
+            [Vec2::from_xy(0, 1), Vec2::from_xy(1, 1), Vec2::from_xy(1, 0)]
+        );
+    }
+
+    #[test]
+    fn adds_signed_vectors_in_three_dimensions() {
+        let pos = Vec3::new([1u32, 2, 3]);
+        assert_eq!(pos.add_signed(Vec3::new([-1, 1, -5])), Vec3::new([0, 3, 0]));
+        assert_eq!(pos.sub_signed(Vec3::new([-1, 1, 2])), Vec3::new([2, 1, 1]));
+        let pos = Vec3::new([i16::MAX, 0, -4]);
+        assert_eq!(pos.add_signed(Vec3::new([1, -1, 4])), Vec3::new([i16::MAX, -1, 0]));
+    }
+
+    #[test]
+    fn rotates_eight_directions() {
+        for dir in Direction8::all() {
+            assert_eq!(dir.rotate_right().rotate_left(), dir);
+            assert_eq!(dir.rotate_right().rotate_right(), dir.rotate_right_90());
+            assert_eq!(dir.rotate_left().rotate_left(), dir.rotate_left_90());
+            assert_eq!(dir.rotate_right_90().rotate_right_90(), dir.opposite());
+            assert_eq!(dir.opposite().opposite(), dir);
+            let unit = dir.unit();
+            assert_eq!(dir.opposite().unit(), Vec2::from_xy(-*unit.x(), -*unit.y()));
+        }
+        let all = Direction8::all();
+        for i in 0..8 {
+            assert_eq!(all[i].rotate_right(), all[(i + 1) % 8]);
+        }
+    }
+
+    #[test]
+    fn converts_between_direction_types() {
+        for dir in Cardinal::all() {
+            let dir8 = Direction8::from(dir);
+            assert_eq!(dir8.as_cardinal(), Some(dir));
+            assert_eq!(dir8.as_diagonal(), None);
+            assert_eq!(dir8.unit(), dir.unit());
+            assert_eq!(dir8.rotate_right_90(), Direction8::from(dir.rotate_right()));
+        }
+        for diag in Diagonal::all() {
+            let dir8 = Direction8::from(diag);
+            assert_eq!(dir8.as_diagonal(), Some(diag));
+            assert_eq!(dir8.as_cardinal(), None);
+            assert_eq!(dir8.rotate_right_90(), Direction8::from(diag.rotate_right()));
+        }
+    }
+
+    #[test]
+    fn eight_way_moves_agree_with_units() {
+        check(usize_pos, |&pos| {
+            for dir in Direction8::all() {
+                if let Some(moved) = dir.try_move(pos) {
+                    assert_eq!(pos.add_signed(dir.unit()), moved, "{dir:?}");
+                    assert_eq!(dir.opposite().try_move(moved), Some(pos), "{dir:?}");
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn parses_arrows_and_compass_letters() {
+        use chumsky::Parser;
+
+        let arrows = "^>v<".chars().map(Cardinal::from_arrow);
+        let letters = "NESW".chars().map(Cardinal::from_compass);
+        for ((arrow, letter), dir) in arrows.zip(letters).zip(Cardinal::all()) {
+            assert_eq!(arrow, Some(dir));
+            assert_eq!(letter, Some(dir));
+        }
+        assert_eq!(Cardinal::from_arrow('N'), None);
+        assert_eq!(Cardinal::from_compass('^'), None);
+
+        assert_eq!(crate::arrow().parse("<").into_result(), Ok(Cardinal::Left));
+        assert_eq!(crate::compass().parse("S").into_result(), Ok(Cardinal::Down));
+        assert!(crate::arrow().parse("x").has_errors());
     }
 }
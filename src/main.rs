@@ -1,18 +1,23 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve, time};
+use advent_of_code::template::commands::{
+    all, download, read, scaffold, solve, stress, time, watch,
+};
+use advent_of_code::template::Error;
 use args::{parse, AppArguments};
+use std::process;
 
 #[cfg(feature = "today")]
-use advent_of_code::template::Day;
-#[cfg(feature = "today")]
-use std::process;
+use advent_of_code::template::{commands::today, Day};
 
 mod args {
-    use advent_of_code::template::Day;
+    use advent_of_code::template::commands::scaffold::{Overwrite, TemplateOptions};
+    use advent_of_code::template::{Config, Day};
+    use std::path::PathBuf;
     use std::process;
 
     pub enum AppArguments {
         Download {
             day: Day,
+            force: bool,
         },
         Read {
             day: Day,
@@ -20,16 +25,33 @@ mod args {
         Scaffold {
             day: Day,
             download: bool,
-            overwrite: bool,
+            overwrite: Overwrite,
+            dry_run: bool,
+            template: TemplateOptions,
         },
         Solve {
             day: Day,
             release: bool,
             dhat: bool,
             submit: Option<u8>,
+            bin_args: Vec<String>,
         },
         All {
             release: bool,
+            inputs: Option<PathBuf>,
+            corpus: Option<PathBuf>,
+        },
+        Watch {
+            day: Day,
+            release: bool,
+        },
+        Stress {
+            day: Day,
+            against: PathBuf,
+            seed: Option<u64>,
+            runs: u64,
+            size: usize,
+            release: bool,
         },
         Time {
             all: bool,
@@ -42,10 +64,13 @@ mod args {
 
     pub fn parse() -> Result<AppArguments, Box<dyn std::error::Error>> {
         let mut args = pico_args::Arguments::from_env();
+        let config = Config::get();
 
         let app_args = match args.subcommand()?.as_deref() {
             Some("all") => AppArguments::All {
-                release: args.contains("--release"),
+                release: args.contains("--release") || config.run.release,
+                inputs: args.opt_value_from_str("--inputs")?,
+                corpus: args.opt_value_from_str("--corpus")?,
             },
             Some("time") => {
                 let all = args.contains("--all");
@@ -59,6 +84,7 @@ mod args {
             }
             Some("download") => AppArguments::Download {
                 day: args.free_from_str()?,
+                force: args.contains("--force"),
             },
             Some("read") => AppArguments::Read {
                 day: args.free_from_str()?,
@@ -66,13 +92,28 @@ mod args {
             Some("scaffold") => AppArguments::Scaffold {
                 day: args.free_from_str()?,
                 download: args.contains("--download"),
-                overwrite: args.contains("--overwrite"),
+                overwrite: parse_overwrite(&mut args)?,
+                dry_run: args.contains("--dry-run"),
+                template: parse_template_options(&mut args)?,
             },
             Some("solve") => AppArguments::Solve {
                 day: args.free_from_str()?,
-                release: args.contains("--release"),
+                release: args.contains("--release") || config.run.release,
                 submit: args.opt_value_from_str("--submit")?,
                 dhat: args.contains("--dhat"),
+                bin_args: parse_bin_args(&mut args)?,
+            },
+            Some("watch") => AppArguments::Watch {
+                day: args.free_from_str()?,
+                release: args.contains("--release") || config.run.release,
+            },
+            Some("stress") => AppArguments::Stress {
+                day: args.free_from_str()?,
+                against: args.value_from_str("--against")?,
+                seed: args.opt_value_from_str("--seed")?,
+                runs: args.opt_value_from_str("--runs")?.unwrap_or(100),
+                size: args.opt_value_from_str("--size")?.unwrap_or(20),
+                release: args.contains("--release") || config.run.release,
             },
             #[cfg(feature = "today")]
             Some("today") => AppArguments::Today,
@@ -93,52 +134,193 @@ mod args {
 
         Ok(app_args)
     }
+
+    /// `--overwrite` without a value only allows replacing the module, as it did before inputs and examples
+    /// were protected. The value may follow after a space, so the flag is only bare if it comes last or is followed
+    /// by another option.
+    fn parse_overwrite(
+        args: &mut pico_args::Arguments,
+    ) -> Result<Overwrite, Box<dyn std::error::Error>> {
+        match args.opt_value_from_str("--overwrite") {
+            Ok(overwrite) => Ok(overwrite.unwrap_or_default()),
+            Err(pico_args::Error::OptionWithoutAValue(_)) => Ok(bare_overwrite(args)),
+            Err(pico_args::Error::Utf8ArgumentParsingFailed { value, .. })
+                if value.starts_with('-') =>
+            {
+                Ok(bare_overwrite(args))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn bare_overwrite(args: &mut pico_args::Arguments) -> Overwrite {
+        args.contains("--overwrite");
+        Overwrite {
+            module: true,
+            ..Overwrite::default()
+        }
+    }
+
+    fn parse_template_options(
+        args: &mut pico_args::Arguments,
+    ) -> Result<TemplateOptions, Box<dyn std::error::Error>> {
+        let default = TemplateOptions::default();
+        let imports: Vec<String> = args.values_from_str("--import")?;
+
+        Ok(TemplateOptions {
+            name: args
+                .opt_value_from_str("--template")?
+                .unwrap_or(default.name),
+            return_type: args
+                .opt_value_from_str("--return-type")?
+                .unwrap_or(default.return_type),
+            part_one_only: args.contains("--part-one-only"),
+            imports: imports
+                .iter()
+                .flat_map(|i| i.split(','))
+                .map(|i| i.trim().to_string())
+                .filter(|i| !i.is_empty())
+                .collect(),
+        })
+    }
+
+    /// Collect the options that are passed through to the solution binary.
+    fn parse_bin_args(
+        args: &mut pico_args::Arguments,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let mut bin_args = vec![];
+
+        for flag in ["--time", "--viz"] {
+            if args.contains(flag) {
+                bin_args.push(flag.into());
+            }
+        }
+
+        for key in ["--part", "--iterations", "--format", "--input", "--viz-delay"] {
+            if let Some(value) = args.opt_value_from_str::<_, String>(key)? {
+                bin_args.extend([key.into(), value]);
+            }
+        }
+
+        // `--example` takes an optional value, so it has to be parsed after every other option.
+        if args.contains("--example") {
+            bin_args.push("--example".into());
+            if let Some(k) = args.opt_free_from_str::<u8>()? {
+                bin_args.push(k.to_string());
+            }
+        }
+
+        Ok(bin_args)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::{parse_overwrite, Overwrite};
+        use std::ffi::OsString;
+
+        /// The parsed `--overwrite` and the arguments it left.
+        fn parse(args: &[&str]) -> (Overwrite, Vec<OsString>) {
+            let mut args = pico_args::Arguments::from_vec(args.iter().map(Into::into).collect());
+            let overwrite = parse_overwrite(&mut args).unwrap();
+            (overwrite, args.finish())
+        }
+
+        const MODULE: Overwrite = Overwrite {
+            module: true,
+            input: false,
+            example: false,
+        };
+
+        #[test]
+        fn parses_overwrite_values() {
+            let module_and_examples = Overwrite {
+                example: true,
+                ..MODULE
+            };
+            assert_eq!(
+                parse(&["--overwrite", "module,examples"]),
+                (module_and_examples, vec![])
+            );
+            assert_eq!(
+                parse(&["--overwrite=module,examples"]),
+                (module_and_examples, vec![])
+            );
+            assert_eq!(parse(&[]), (Overwrite::default(), vec![]));
+        }
+
+        #[test]
+        fn bare_overwrite_only_replaces_the_module() {
+            assert_eq!(parse(&["--overwrite"]), (MODULE, vec![]));
+            assert_eq!(
+                parse(&["--overwrite", "--dry-run"]),
+                (MODULE, vec!["--dry-run".into()])
+            );
+        }
+
+        #[test]
+        fn rejects_unknown_kinds() {
+            let mut args =
+                pico_args::Arguments::from_vec(vec!["--overwrite".into(), "modle".into()]);
+            assert!(parse_overwrite(&mut args).is_err());
+        }
+    }
+}
+
+fn run(args: AppArguments) -> Result<(), Error> {
+    match args {
+        AppArguments::All {
+            release,
+            inputs,
+            corpus,
+        } => all::handle(release, inputs.as_deref(), corpus.as_deref()),
+        AppArguments::Time { day, all, store } => time::handle(day, all, store),
+        AppArguments::Download { day, force } => download::handle(day, force),
+        AppArguments::Read { day } => read::handle(day),
+        AppArguments::Scaffold {
+            day,
+            download,
+            overwrite,
+            dry_run,
+            template,
+        } => {
+            scaffold::handle(day, &overwrite, dry_run, &template)?;
+            if download && !dry_run {
+                download::handle(day, false)?;
+            }
+            Ok(())
+        }
+        AppArguments::Solve {
+            day,
+            release,
+            dhat,
+            submit,
+            bin_args,
+        } => solve::handle(day, release, dhat, submit, &bin_args),
+        AppArguments::Watch { day, release } => watch::handle(day, release),
+        AppArguments::Stress {
+            day,
+            against,
+            seed,
+            runs,
+            size,
+            release,
+        } => stress::handle(day, &against, seed, runs, size, release),
+        #[cfg(feature = "today")]
+        AppArguments::Today => today::handle(Day::today()),
+    }
 }
 
 fn main() {
-    match parse() {
+    let args = match parse() {
+        Ok(args) => args,
         Err(err) => {
             eprintln!("Error: {err}");
-            std::process::exit(1);
+            process::exit(1);
         }
-        Ok(args) => match args {
-            AppArguments::All { release } => all::handle(release),
-            AppArguments::Time { day, all, store } => time::handle(day, all, store),
-            AppArguments::Download { day } => download::handle(day),
-            AppArguments::Read { day } => read::handle(day),
-            AppArguments::Scaffold {
-                day,
-                download,
-                overwrite,
-            } => {
-                scaffold::handle(day, overwrite);
-                if download {
-                    download::handle(day);
-                }
-            }
-            AppArguments::Solve {
-                day,
-                release,
-                dhat,
-                submit,
-            } => solve::handle(day, release, dhat, submit),
-            #[cfg(feature = "today")]
-            AppArguments::Today => {
-                match Day::today() {
-                    Some(day) => {
-                        scaffold::handle(day, false);
-                        download::handle(day);
-                        read::handle(day)
-                    }
-                    None => {
-                        eprintln!(
-                            "`today` command can only be run between the 1st and \
-                            the 25th of december. Please use `scaffold` with a specific day."
-                        );
-                        process::exit(1)
-                    }
-                };
-            }
-        },
     };
+
+    if let Err(err) = run(args) {
+        eprintln!("Error: {err}");
+        process::exit(err.exit_code());
+    }
 }
@@ -0,0 +1,93 @@
+//! A minimal property testing harness for the library's own tests.
+//!
+//! [`check`] runs a property on values generated with [`crate::gen::Rng`]. Every case has its own seed, which is
+//! printed when the property fails, so the failing case can be rerun on its own:
+//! `AOC_PROP_SEED=<seed> AOC_PROP_CASES=1 cargo test --features test_lib <test>`.
+
+use std::env;
+use std::fmt::Debug;
+use std::ops::RangeInclusive;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::gen::Rng;
+
+/// The number of cases per property, `AOC_PROP_CASES` or 256. Miri is a lot slower, so it only runs 16 by default.
+pub fn cases() -> u64 {
+    env::var("AOC_PROP_CASES")
+        .ok()
+        .and_then(|cases| cases.parse().ok())
+        .unwrap_or(if cfg!(miri) { 16 } else { 256 })
+}
+
+/// The seed of the first case, `AOC_PROP_SEED` or a fixed seed, so test runs are reproducible.
+pub fn seed() -> u64 {
+    env::var("AOC_PROP_SEED")
+        .ok()
+        .and_then(|seed| seed.parse().ok())
+        .unwrap_or(0x5eed)
+}
+
+/// Check that `property` holds for values produced by `generate`. The property fails by panicking, e.g. with
+/// `assert_eq!`.
+pub fn check<T: Debug>(generate: impl Fn(&mut Rng) -> T, property: impl Fn(&T)) {
+    let seed = seed();
+    for case_seed in (0..cases()).map(|case| seed.wrapping_add(case)) {
+        let value = generate(&mut Rng::new(case_seed));
+        if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| property(&value))) {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_default();
+            panic!("property failed for {value:?} (seed {case_seed}): {message}");
+        }
+    }
+}
+
+/// A `usize` that is often `0`, `1`, `usize::MAX - 1` or `usize::MAX`, to cover overflows.
+pub fn usize_with_edges(rng: &mut Rng) -> usize {
+    match rng.range(0..=7) {
+        0 => 0,
+        1 => 1,
+        2 => usize::MAX - 1,
+        3 => usize::MAX,
+        4 | 5 => rng.range(0..=1000) as usize,
+        _ => rng.next_u64() as usize,
+    }
+}
+
+/// An `i64` that is often `0`, `i64::MIN`, `i64::MAX` or next to them, to cover overflows.
+pub fn i64_with_edges(rng: &mut Rng) -> i64 {
+    match rng.range(0..=7) {
+        0 => 0,
+        1 => i64::MIN,
+        2 => i64::MIN + 1,
+        3 => i64::MAX,
+        4 => i64::MAX - 1,
+        5 => rng.range(0..=2000) as i64 - 1000,
+        _ => rng.next_u64() as i64,
+    }
+}
+
+/// A vector with a length in `len`, with elements produced by `element`.
+pub fn vec_of<T>(
+    rng: &mut Rng,
+    len: RangeInclusive<u64>,
+    mut element: impl FnMut(&mut Rng) -> T,
+) -> Vec<T> {
+    let len = rng.range(len);
+    (0..len).map(|_| element(rng)).collect()
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(test)]
+mod tests {
+    use super::check;
+
+    #[test]
+    #[should_panic(expected = "property failed for 9 (seed")]
+    fn reports_failing_values() {
+        check(|rng| rng.range(0..=9), |&value| assert!(value < 9));
+    }
+}
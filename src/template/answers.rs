@@ -0,0 +1,257 @@
+/// Expected answers for example inputs, used by `cargo solve <day> --example`.
+///
+/// Answers are read from an answers file next to the example (e.g. `data/examples/12-2.answers`):
+///
+/// ```toml
+/// part_one = 1930
+/// part_two = 1206
+/// ```
+///
+/// Parts without an entry fall back to the value asserted by the matching test in the solution's test module.
+use std::{fs, path::Path};
+
+use toml::{Table, Value};
+
+use crate::template::{Config, Day};
+
+/// The expected answers for a single input.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Answers {
+    pub part_one: Option<String>,
+    pub part_two: Option<String>,
+}
+
+impl Answers {
+    pub fn get(&self, part: u8) -> Option<&str> {
+        match part {
+            1 => self.part_one.as_deref(),
+            2 => self.part_two.as_deref(),
+            _ => None,
+        }
+    }
+
+    fn set(&mut self, part: u8, answer: String) {
+        match part {
+            1 => self.part_one = Some(answer),
+            2 => self.part_two = Some(answer),
+            _ => {}
+        }
+    }
+
+    /// Fill in missing answers from `other`.
+    pub fn or(self, other: Answers) -> Answers {
+        Answers {
+            part_one: self.part_one.or(other.part_one),
+            part_two: self.part_two.or(other.part_two),
+        }
+    }
+
+    /// Read an answers file. If not present, returns no answers.
+    pub fn read_from_file(path: impl AsRef<Path>) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Answers::try_from(contents.as_str()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Answers::default()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
+
+/// Returns the path of the answers file for an example, e.g. `data/examples/12-2.answers`.
+pub fn get_answers_path(day: Day, example: Option<u8>) -> std::path::PathBuf {
+    let examples = &Config::get().paths.examples;
+    match example {
+        Some(k) => examples.join(format!("{day}-{k}.answers")),
+        None => examples.join(format!("{day}.answers")),
+    }
+}
+
+/// Collect the expected answers for an example from its answers file and the solution's test module.
+pub fn read_example_answers(day: Day, example: Option<u8>) -> Answers {
+    let from_file = Answers::read_from_file(get_answers_path(day, example)).unwrap_or_else(|e| {
+        eprintln!("Failed to read answers file: {e}");
+        Answers::default()
+    });
+
+    let from_tests = fs::read_to_string(Config::get().bin_path(day))
+        .map(|source| parse_test_module(&source, example))
+        .unwrap_or_default();
+
+    from_file.or(from_tests)
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl TryFrom<&str> for Answers {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let toml = value.parse::<Table>().map_err(|e| e.to_string())?;
+        let mut answers = Answers::default();
+
+        for (part, key) in [(1, "part_one"), (2, "part_two")] {
+            match toml.get(key) {
+                None => {}
+                Some(Value::String(s)) => answers.set(part, s.clone()),
+                Some(Value::Integer(i)) => answers.set(part, i.to_string()),
+                Some(_) => return Err(format!("expected `{key}` to be a number or string.")),
+            }
+        }
+
+        Ok(answers)
+    }
+}
+
+/// Extract the expected answers for an example from the `assert_eq!(result, Some(..))` checks of a test module.
+///
+/// Tests are matched to the example by the `read_file("examples", DAY)` or
+/// `read_file_part("examples", DAY, k)` call they make.
+pub fn parse_test_module(source: &str, example: Option<u8>) -> Answers {
+    let mut answers = Answers::default();
+
+    let Some(tests_start) = source.find("mod tests") else {
+        return answers;
+    };
+
+    for test in source[tests_start..].split("#[test]").skip(1) {
+        let compact: String = test.chars().filter(|c| !c.is_whitespace()).collect();
+
+        let part = if compact.contains("part_one(") {
+            1
+        } else if compact.contains("part_two(") {
+            2
+        } else {
+            continue;
+        };
+
+        let test_example = if let Some(idx) = compact.find("read_file_part(\"examples\",DAY,") {
+            let rest = &compact[idx + "read_file_part(\"examples\",DAY,".len()..];
+            let digits: String = rest.chars().take_while(char::is_ascii_digit).collect();
+            match digits.parse() {
+                Ok(k) => Some(k),
+                Err(_) => continue,
+            }
+        } else if compact.contains("read_file(\"examples\",DAY)") {
+            None
+        } else {
+            continue;
+        };
+
+        if test_example != example {
+            continue;
+        }
+
+        if let Some(expected) = parse_expected(&compact) {
+            answers.set(part, expected);
+        }
+    }
+
+    answers
+}
+
+/// Parse the expected value of `assert_eq!(result, Some(..))`. Returns `None` for `None`.
+fn parse_expected(compact: &str) -> Option<String> {
+    const ASSERT: &str = "assert_eq!(result,";
+
+    let rest = &compact[compact.find(ASSERT)? + ASSERT.len()..];
+
+    // find the closing parenthesis of `assert_eq!`.
+    let mut depth = 0;
+    let end = rest.char_indices().find_map(|(i, c)| {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return Some(i),
+            ')' => depth -= 1,
+            _ => {}
+        }
+        None
+    })?;
+
+    let value = rest[..end].strip_prefix("Some(")?.strip_suffix(')')?;
+    let value = value.strip_suffix(".to_string()").unwrap_or(value);
+    let value = value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value);
+
+    Some(value.to_string())
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{parse_test_module, Answers};
+
+    const SOURCE: &str = r#"
+advent_of_code::solution!(12);
+
+pub fn part_one(input: &str) -> Option<u64> { None }
+pub fn part_two(input: &str) -> Option<u64> { None }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_one() {
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Some(1930));
+    }
+
+    #[test]
+    fn test_part_two() {
+        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn test_part_two_2() {
+        let result = part_two(&advent_of_code::template::read_file_part(
+            "examples", DAY, 2,
+        ));
+        assert_eq!(result, Some(80));
+    }
+
+    #[test]
+    fn test_helper() {
+        assert_eq!(part_one("AAAA"), Some(40));
+    }
+}
+"#;
+
+    #[test]
+    fn parses_test_module_for_main_example() {
+        let answers = parse_test_module(SOURCE, None);
+        assert_eq!(answers.part_one.as_deref(), Some("1930"));
+        assert_eq!(answers.part_two, None);
+    }
+
+    #[test]
+    fn parses_test_module_for_numbered_example() {
+        let answers = parse_test_module(SOURCE, Some(2));
+        assert_eq!(answers.part_one, None);
+        assert_eq!(answers.part_two.as_deref(), Some("80"));
+        assert_eq!(parse_test_module(SOURCE, Some(3)), Answers::default());
+    }
+
+    #[test]
+    fn parses_answers_file() {
+        let answers = Answers::try_from("part_one = 11\npart_two = \"abc\"").unwrap();
+        assert_eq!(answers.get(1), Some("11"));
+        assert_eq!(answers.get(2), Some("abc"));
+    }
+
+    #[test]
+    fn prefers_answers_file() {
+        let file = Answers::try_from("part_two = 81").unwrap();
+        let answers = file.or(parse_test_module(SOURCE, None));
+        assert_eq!(answers.get(1), Some("1930"));
+        assert_eq!(answers.get(2), Some("81"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_invalid_answers() {
+        Answers::try_from("part_one = [1, 2]").unwrap();
+    }
+}
@@ -4,7 +4,7 @@ use std::{
     process::{Command, Output, Stdio},
 };
 
-use crate::template::Day;
+use crate::template::{Config, Day};
 
 #[derive(Debug)]
 pub enum AocCommandError {
@@ -49,26 +49,32 @@ pub fn read(day: Day) -> Result<Output, AocCommandError> {
     call_aoc_cli(&args)
 }
 
-pub fn download(day: Day) -> Result<Output, AocCommandError> {
+/// Download the input and/or the puzzle description of a day, replacing existing files.
+pub fn download(day: Day, input: bool, puzzle: bool) -> Result<Output, AocCommandError> {
     let input_path = get_input_path(day);
     let puzzle_path = get_puzzle_path(day);
 
-    let args = build_args(
-        "download",
-        &[
-            "--overwrite".into(),
-            "--input-file".into(),
-            input_path.to_string(),
-            "--puzzle-file".into(),
-            puzzle_path.to_string(),
-        ],
-        day,
-    );
+    let mut args = vec![
+        "--overwrite".into(),
+        "--input-file".into(),
+        input_path.to_string(),
+        "--puzzle-file".into(),
+        puzzle_path.to_string(),
+    ];
+    match (input, puzzle) {
+        (true, false) => args.push("--input-only".into()),
+        (false, true) => args.push("--puzzle-only".into()),
+        _ => {}
+    }
 
-    let output = call_aoc_cli(&args)?;
+    let output = call_aoc_cli(&build_args("download", &args, day))?;
     println!("---");
-    println!("🎄 Successfully wrote input to \"{}\".", &input_path);
-    println!("🎄 Successfully wrote puzzle to \"{}\".", &puzzle_path);
+    if input {
+        println!("🎄 Successfully wrote input to \"{}\".", &input_path);
+    }
+    if puzzle {
+        println!("🎄 Successfully wrote puzzle to \"{}\".", &puzzle_path);
+    }
     Ok(output)
 }
 
@@ -81,18 +87,15 @@ pub fn submit(day: Day, part: u8, result: &str) -> Result<Output, AocCommandErro
 }
 
 fn get_input_path(day: Day) -> String {
-    format!("data/inputs/{day}.txt")
+    Config::get().input_path(day).display().to_string()
 }
 
 fn get_puzzle_path(day: Day) -> String {
-    format!("data/puzzles/{day}.md")
+    Config::get().puzzle_path(day).display().to_string()
 }
 
 fn get_year() -> Option<u16> {
-    match std::env::var("AOC_YEAR") {
-        Ok(x) => x.parse().ok().or(None),
-        Err(_) => None,
-    }
+    Config::get().year()
 }
 
 fn build_args(command: &str, args: &[String], day: Day) -> Vec<String> {
@@ -0,0 +1,352 @@
+/// Command-line arguments understood by the solution binaries generated by `solution!`.
+use std::{
+    ffi::OsString,
+    fmt::Display,
+    fs,
+    io::{self, Read},
+    path::PathBuf,
+    str::FromStr,
+    time::Duration,
+};
+
+use crate::template::{Config, Day};
+
+pub const USAGE: &str = "\
+Usage: cargo solve <day> [options]
+
+Options:
+    --time              bench the solution
+    --iterations <n>    bench the solution with exactly <n> samples
+    --part <1|2>        only run a single part
+    --submit <1|2>      submit the result of a part for the puzzle input via aoc-cli
+    --input <path|->    read the input from a file, or from stdin if `-`
+    --example [k]       read the input from the example file `DD.txt` or `DD-k.txt`
+    --format <fmt>      output format, `human` (default) or `json`
+    --viz               show the visualisations of the solution on stderr
+    --viz-delay <ms>    animate the visualisations, showing each frame for <ms> milliseconds";
+
+/// Where a solution reads its input from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InputSource {
+    /// The puzzle input in the configured inputs folder.
+    Puzzle,
+    /// An example file, optionally with a part suffix like `01-2.txt`.
+    Example(Option<u8>),
+    Path(PathBuf),
+    Stdin,
+}
+
+impl Display for InputSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputSource::Puzzle => f.write_str("puzzle input"),
+            InputSource::Example(None) => f.write_str("example"),
+            InputSource::Example(Some(k)) => write!(f, "example {k}"),
+            InputSource::Path(path) => write!(f, "\"{}\"", path.display()),
+            InputSource::Stdin => f.write_str("stdin"),
+        }
+    }
+}
+
+/// How results are printed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    #[default]
+    Human,
+    /// One JSON object per part and line.
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(OutputFormat::Human),
+            "json" => Ok(OutputFormat::Json),
+            s => Err(format!("unknown format `{s}`, expected `human` or `json`")),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SolutionArgs {
+    pub time: bool,
+    pub iterations: Option<u128>,
+    pub part: Option<u8>,
+    pub submit: Option<u8>,
+    pub input: InputSource,
+    pub format: OutputFormat,
+    pub viz: bool,
+    /// Animate the visualisations with this delay between frames.
+    pub viz_delay: Option<Duration>,
+}
+
+impl Default for SolutionArgs {
+    fn default() -> Self {
+        Self {
+            time: false,
+            iterations: None,
+            part: None,
+            submit: None,
+            input: InputSource::Puzzle,
+            format: OutputFormat::Human,
+            viz: false,
+            viz_delay: None,
+        }
+    }
+}
+
+/// An error which can be returned when parsing [`SolutionArgs`].
+#[derive(Debug)]
+pub struct ArgsError(String);
+
+impl std::error::Error for ArgsError {}
+
+impl Display for ArgsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<pico_args::Error> for ArgsError {
+    fn from(e: pico_args::Error) -> Self {
+        ArgsError(e.to_string())
+    }
+}
+
+impl SolutionArgs {
+    pub fn from_env() -> Result<Self, ArgsError> {
+        Self::parse(std::env::args_os().skip(1).collect())
+    }
+
+    pub fn parse(args: Vec<OsString>) -> Result<Self, ArgsError> {
+        let mut args = pico_args::Arguments::from_vec(args);
+
+        let time = args.contains("--time");
+        let iterations = args.opt_value_from_str("--iterations")?;
+        let part = args.opt_value_from_fn("--part", parse_part)?;
+        let submit = args.opt_value_from_fn("--submit", parse_part)?;
+        let format = args
+            .opt_value_from_str::<_, OutputFormat>("--format")?
+            .unwrap_or_default();
+        let viz_delay = args
+            .opt_value_from_str::<_, u64>("--viz-delay")?
+            .map(Duration::from_millis);
+        let viz = args.contains("--viz") || viz_delay.is_some();
+        let input_path: Option<String> = args.opt_value_from_str("--input")?;
+        let example = args.contains("--example");
+
+        let input = match (input_path, example) {
+            (Some(_), true) => {
+                return Err(ArgsError(
+                    "`--input` and `--example` can not be combined".into(),
+                ))
+            }
+            (Some(path), false) if path == "-" => InputSource::Stdin,
+            (Some(path), false) => InputSource::Path(path.into()),
+            // the example index is an optional value, so it is the only free argument left.
+            (None, true) => InputSource::Example(args.opt_free_from_fn(parse_example)?),
+            (None, false) => InputSource::Puzzle,
+        };
+
+        // an answer for another input would spend one of the limited attempts, and may lock out submitting.
+        if submit.is_some() && input != InputSource::Puzzle {
+            return Err(ArgsError(
+                "`--submit` only works with the puzzle input".into(),
+            ));
+        }
+
+        if iterations == Some(0) {
+            return Err(ArgsError("`--iterations` must be at least 1".into()));
+        }
+
+        // benching runs the solution many times, which would show every frame again.
+        if viz && (time || iterations.is_some()) {
+            return Err(ArgsError(
+                "`--viz` can not be combined with `--time` or `--iterations`".into(),
+            ));
+        }
+
+        let remaining = args.finish();
+        if !remaining.is_empty() {
+            return Err(ArgsError(format!("unknown argument(s): {remaining:?}")));
+        }
+
+        Ok(Self {
+            time,
+            iterations,
+            part,
+            submit,
+            input,
+            format,
+            viz,
+            viz_delay,
+        })
+    }
+
+    /// Whether the solution should be benched after its first run.
+    pub fn is_timed(&self) -> bool {
+        self.time || self.iterations.is_some()
+    }
+
+    /// Whether the given part should be run.
+    pub fn runs_part(&self, part: u8) -> bool {
+        self.part.is_none_or(|p| p == part)
+    }
+
+    /// Reads the input selected by `--input` or `--example`.
+    pub fn read_input(&self, day: Day) -> io::Result<String> {
+        let config = Config::get();
+        match &self.input {
+            InputSource::Puzzle => fs::read_to_string(config.input_path(day)),
+            InputSource::Example(None) => fs::read_to_string(config.example_path(day)),
+            InputSource::Example(Some(k)) => {
+                fs::read_to_string(config.paths.examples.join(format!("{day}-{k}.txt")))
+            }
+            InputSource::Path(path) => fs::read_to_string(path),
+            InputSource::Stdin => {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input)?;
+                Ok(input)
+            }
+        }
+    }
+
+    /// The params file that belongs to the selected input, e.g. `data/examples/14.params`.
+    pub fn params_path(&self, day: Day) -> Option<PathBuf> {
+        let config = Config::get();
+        match &self.input {
+            InputSource::Puzzle => Some(config.paths.inputs.join(format!("{day}.params"))),
+            InputSource::Example(None) => Some(config.paths.examples.join(format!("{day}.params"))),
+            InputSource::Example(Some(k)) => {
+                Some(config.paths.examples.join(format!("{day}-{k}.params")))
+            }
+            InputSource::Path(path) => Some(path.with_extension("params")),
+            InputSource::Stdin => None,
+        }
+    }
+}
+
+fn parse_part(s: &str) -> Result<u8, String> {
+    match s.parse() {
+        Ok(part @ (1 | 2)) => Ok(part),
+        _ => Err(format!("expected part `1` or `2`, got `{s}`")),
+    }
+}
+
+fn parse_example(s: &str) -> Result<u8, String> {
+    s.parse()
+        .map_err(|_| format!("expected an example number, got `{s}`"))
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::Duration;
+
+    use super::{InputSource, OutputFormat, SolutionArgs};
+
+    fn parse(args: &[&str]) -> Result<SolutionArgs, super::ArgsError> {
+        SolutionArgs::parse(args.iter().map(Into::into).collect())
+    }
+
+    #[test]
+    fn parses_no_arguments() {
+        assert_eq!(parse(&[]).unwrap(), SolutionArgs::default());
+    }
+
+    #[test]
+    fn parses_all_arguments() {
+        let args = parse(&[
+            "--time",
+            "--submit",
+            "2",
+            "--part",
+            "2",
+            "--iterations",
+            "5",
+            "--format",
+            "json",
+        ])
+        .unwrap();
+        assert_eq!(args.time, true);
+        assert_eq!(args.submit, Some(2));
+        assert_eq!(args.part, Some(2));
+        assert_eq!(args.input, InputSource::Puzzle);
+        assert_eq!(args.iterations, Some(5));
+        assert_eq!(args.format, OutputFormat::Json);
+        assert_eq!(args.runs_part(1), false);
+    }
+
+    #[test]
+    fn parses_input_sources() {
+        assert_eq!(parse(&["--input", "-"]).unwrap().input, InputSource::Stdin);
+        assert_eq!(
+            parse(&["--example"]).unwrap().input,
+            InputSource::Example(None)
+        );
+        assert_eq!(
+            parse(&["--example", "2"]).unwrap().input,
+            InputSource::Example(Some(2))
+        );
+        assert_eq!(
+            parse(&["--example", "3", "--time"]).unwrap().input,
+            InputSource::Example(Some(3))
+        );
+    }
+
+    #[test]
+    fn iterations_imply_benching() {
+        assert_eq!(parse(&["--iterations", "10"]).unwrap().is_timed(), true);
+        assert_eq!(parse(&[]).unwrap().is_timed(), false);
+    }
+
+    #[test]
+    fn parses_viz_options() {
+        let args = parse(&["--viz"]).unwrap();
+        assert_eq!((args.viz, args.viz_delay), (true, None));
+        // a delay implies `--viz`.
+        let args = parse(&["--viz-delay", "50"]).unwrap();
+        assert_eq!((args.viz, args.viz_delay), (true, Some(Duration::from_millis(50))));
+        assert!(parse(&["--viz", "--time"]).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_invalid_part() {
+        parse(&["--part", "3"]).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_missing_submit_part() {
+        parse(&["--submit"]).unwrap();
+    }
+
+    #[test]
+    fn only_submits_the_puzzle_input() {
+        for input in [
+            &["--example"][..],
+            &["--example", "2"],
+            &["--input", "other.txt"],
+            &["--input", "-"],
+        ] {
+            let args = [&["--submit", "1"][..], input].concat();
+            assert!(parse(&args).is_err(), "accepted {args:?}");
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_conflicting_inputs() {
+        parse(&["--input", "a.txt", "--example"]).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_unknown_arguments() {
+        parse(&["--frobnicate"]).unwrap();
+    }
+}
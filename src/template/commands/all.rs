@@ -1,5 +1,12 @@
-use crate::template::{all_days, run_multi::run_multi};
+use std::path::Path;
 
-pub fn handle(is_release: bool) {
-    run_multi(&all_days().collect(), is_release, false);
+use crate::template::{all_days, corpus::run_corpus, run_multi::run_multi, Error};
+
+pub fn handle(is_release: bool, inputs: Option<&Path>, corpus: Option<&Path>) -> Result<(), Error> {
+    if let Some(corpus) = corpus {
+        return run_corpus(corpus, is_release);
+    }
+
+    run_multi(&all_days().collect(), is_release, false, inputs)?;
+    Ok(())
 }
@@ -1,14 +1,59 @@
-use crate::template::{aoc_cli, Day};
-use std::process;
+use std::fs;
 
-pub fn handle(day: Day) {
-    if aoc_cli::check().is_err() {
-        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
-        process::exit(1);
+use crate::template::input::{check_input, read_checked, InputCache};
+use crate::template::{aoc_cli, Config, Day, Error};
+
+/// Download the input and puzzle description of a day.
+///
+/// Files that are already present are not downloaded again unless `force` is set. Missing inputs are restored from the
+/// input cache if possible, and downloaded inputs are checked and added to the cache.
+pub fn handle(day: Day, force: bool) -> Result<(), Error> {
+    let config = Config::get();
+    let input_path = config.input_path(day);
+    let puzzle_path = config.puzzle_path(day);
+    let cache = config.year().map(|year| (year, InputCache::from_config()));
+
+    let mut has_input = !force && read_checked(&input_path).is_ok();
+    let has_puzzle = !force && puzzle_path.exists();
+
+    if !has_input && !force {
+        if let Some(cached) = cache.as_ref().and_then(|(year, c)| c.get(*year, day)) {
+            fs::write(&input_path, cached).map_err(|source| Error::File {
+                path: input_path.clone(),
+                source,
+            })?;
+            println!(
+                "🎄 Restored input from cache to \"{}\".",
+                input_path.display()
+            );
+            has_input = true;
+        }
+    }
+
+    if has_input && has_puzzle {
+        println!("🎄 Input and puzzle are already present. Pass `--force` to download them again.");
+        return Ok(());
+    }
+
+    aoc_cli::check()?;
+    aoc_cli::download(day, !has_input, !has_puzzle)?;
+
+    if !has_input {
+        let contents = fs::read_to_string(&input_path).map_err(|source| Error::File {
+            path: input_path.clone(),
+            source,
+        })?;
+        check_input(&contents).map_err(|problem| Error::InvalidInput {
+            path: input_path.clone(),
+            problem,
+        })?;
+
+        if let Some((year, cache)) = &cache {
+            if let Err(e) = cache.put(*year, day, &contents) {
+                eprintln!("Failed to cache input: {e}");
+            }
+        }
     }
 
-    if let Err(e) = aoc_cli::download(day) {
-        eprintln!("failed to call aoc-cli: {e}");
-        process::exit(1);
-    };
+    Ok(())
 }
@@ -3,4 +3,7 @@ pub mod download;
 pub mod read;
 pub mod scaffold;
 pub mod solve;
+pub mod stress;
 pub mod time;
+pub mod today;
+pub mod watch;
@@ -1,79 +1,563 @@
 use std::{
-    fs::{File, OpenOptions},
+    fmt::Display,
+    fs::{self, OpenOptions},
     io::Write,
-    process,
+    path::{Path, PathBuf},
+    str::FromStr,
 };
 
-use crate::template::Day;
+use crate::template::{Config, Day, Error};
 
 const MODULE_TEMPLATE: &str =
     include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/template.txt"));
 
-fn safe_create_file(path: &str, overwrite: bool) -> Result<File, std::io::Error> {
-    let mut file = OpenOptions::new();
-    if overwrite {
-        file.create(true);
-    } else {
-        file.create_new(true);
+/// Options for rendering a module template.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TemplateOptions {
+    /// Name of a template in the templates folder, without the `.txt` extension.
+    pub name: String,
+    /// Return type of the generated `part_one` and `part_two` functions.
+    pub return_type: String,
+    /// Only generate `part_one`, declared with `solution!(DD, 1)`.
+    pub part_one_only: bool,
+    /// Library items to import, e.g. `Cardinal` or `fast_cartesian::MultiProduct`.
+    pub imports: Vec<String>,
+}
+
+impl Default for TemplateOptions {
+    fn default() -> Self {
+        Self {
+            name: "default".into(),
+            return_type: "u32".into(),
+            part_one_only: false,
+            imports: vec![],
+        }
     }
-    file.truncate(true).write(true).open(path)
 }
 
-fn create_file(path: &str) -> Result<File, std::io::Error> {
-    OpenOptions::new()
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open(path)
+/// Read a named template from the templates folder.
+/// The `default` template falls back to the built-in template if the folder does not override it.
+fn read_template(name: &str) -> Result<String, String> {
+    let templates = &Config::get().paths.templates;
+    let path = templates.join(format!("{name}.txt"));
+    match fs::read_to_string(&path) {
+        Ok(template) => Ok(template),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound && name == "default" => {
+            Ok(MODULE_TEMPLATE.to_string())
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            let mut available = list_templates(templates);
+            if !available.iter().any(|t| t == "default") {
+                available.push("default".into());
+            }
+            available.sort();
+            Err(format!(
+                "unknown template `{name}`, available templates: {}",
+                available.join(", ")
+            ))
+        }
+        Err(e) => Err(format!("could not read \"{}\": {e}", path.display())),
+    }
 }
 
-pub fn handle(day: Day, overwrite: bool) {
-    let input_path = format!("data/inputs/{day}.txt");
-    let example_path = format!("data/examples/{day}.txt");
-    let module_path = format!("src/bin/{day}.rs");
+fn list_templates(dir: &Path) -> Vec<String> {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .filter_map(|entry| {
+                    let name = entry.file_name().into_string().ok()?;
+                    name.strip_suffix(".txt").map(String::from)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
 
-    let mut file = match safe_create_file(&module_path, overwrite) {
-        Ok(file) => file,
-        Err(e) => {
-            eprintln!("Failed to create module file: {e}");
-            process::exit(1);
-        }
+/// Substitute the placeholders of a module template:
+///
+/// - `%DAY_NUMBER%` and `%RETURN_TYPE%`
+/// - `%YEAR%`. Lines with it are removed if no year is configured.
+/// - `%SOLUTION_ARGS%`, the arguments of `solution!`, i.e. `DD` or `DD, 1`
+/// - `%IMPORTS%`, a `use advent_of_code::{..};` line without the items the template already imports from
+///   `advent_of_code`. The line is removed if there is nothing to import.
+/// - lines between `%BEGIN_PART_TWO%` and `%END_PART_TWO%`, which are removed for part-one-only solutions.
+pub fn render_template(
+    template: &str,
+    day: Day,
+    year: Option<u16>,
+    options: &TemplateOptions,
+) -> String {
+    let day_number = day.into_inner().to_string();
+    let solution_args = if options.part_one_only {
+        format!("{day_number}, 1")
+    } else {
+        day_number.clone()
+    };
+    let imported = imported_items(template);
+    let imports = options
+        .imports
+        .iter()
+        .map(String::as_str)
+        .filter(|import| !imported.contains(import))
+        .collect::<Vec<_>>();
+    let imports = match imports.as_slice() {
+        [] => String::new(),
+        [import] => format!("use advent_of_code::{import};"),
+        imports => format!("use advent_of_code::{{{}}};", imports.join(", ")),
     };
+    let year = year.map(|y| y.to_string());
+
+    let mut out = String::with_capacity(template.len());
+    let mut in_part_two = false;
 
-    match file.write_all(
-        MODULE_TEMPLATE
-            .replace("%DAY_NUMBER%", &day.into_inner().to_string())
-            .as_bytes(),
-    ) {
-        Ok(()) => {
-            println!("Created module file \"{}\"", &module_path);
+    for line in template.split_inclusive('\n') {
+        match line.trim() {
+            "%BEGIN_PART_TWO%" => in_part_two = true,
+            "%END_PART_TWO%" => in_part_two = false,
+            "%IMPORTS%" if imports.is_empty() => {}
+            _ if in_part_two && options.part_one_only => {}
+            _ if year.is_none() && line.contains("%YEAR%") => {}
+            _ => out.push_str(
+                &line
+                    .replace("%DAY_NUMBER%", &day_number)
+                    .replace("%YEAR%", year.as_deref().unwrap_or_default())
+                    .replace("%RETURN_TYPE%", &options.return_type)
+                    .replace("%SOLUTION_ARGS%", &solution_args)
+                    .replace("%IMPORTS%", &imports),
+            ),
         }
-        Err(e) => {
-            eprintln!("Failed to write module contents: {e}");
-            process::exit(1);
+    }
+
+    out
+}
+
+/// The items of the `use advent_of_code::..;` lines of a template, e.g. `Cardinal` and `Parser` for
+/// `use advent_of_code::{Cardinal, Parser};`.
+fn imported_items(template: &str) -> Vec<&str> {
+    template
+        .lines()
+        .filter_map(|line| {
+            line.trim()
+                .strip_prefix("use advent_of_code::")?
+                .strip_suffix(';')
+        })
+        .flat_map(|items| {
+            items
+                .trim_start_matches('{')
+                .trim_end_matches('}')
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+        })
+        .collect()
+}
+
+/// The files created by `scaffold`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FileKind {
+    Module,
+    Input,
+    Example,
+}
+
+impl Display for FileKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileKind::Module => f.write_str("module"),
+            FileKind::Input => f.write_str("input"),
+            FileKind::Example => f.write_str("example"),
         }
     }
+}
+
+/// The kinds of existing files that `scaffold` may replace, e.g. `--overwrite=module,examples`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Overwrite {
+    pub module: bool,
+    pub input: bool,
+    pub example: bool,
+}
 
-    match create_file(&input_path) {
-        Ok(_) => {
-            println!("Created empty input file \"{}\"", &input_path);
+impl Overwrite {
+    pub fn allows(&self, kind: FileKind) -> bool {
+        match kind {
+            FileKind::Module => self.module,
+            FileKind::Input => self.input,
+            FileKind::Example => self.example,
         }
-        Err(e) => {
-            eprintln!("Failed to create input file: {e}");
-            process::exit(1);
+    }
+}
+
+const KINDS: &str = "`module`, `inputs`, `examples` or `all`";
+
+impl FromStr for Overwrite {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut overwrite = Overwrite::default();
+        for kind in s.split(',').map(str::trim) {
+            match kind {
+                "module" => overwrite.module = true,
+                "input" | "inputs" => overwrite.input = true,
+                "example" | "examples" => overwrite.example = true,
+                "all" => {
+                    overwrite = Overwrite {
+                        module: true,
+                        input: true,
+                        example: true,
+                    }
+                }
+                kind => return Err(format!("unknown file kind `{kind}`, expected {KINDS}")),
+            }
         }
+        Ok(overwrite)
     }
+}
 
-    match create_file(&example_path) {
-        Ok(_) => {
-            println!("Created empty example file \"{}\"", &example_path);
+/// What `scaffold` does with a single file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Create,
+    Overwrite,
+    /// The file exists and may not be overwritten.
+    Keep,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlannedFile {
+    pub kind: FileKind,
+    pub path: PathBuf,
+    pub contents: String,
+    pub action: Action,
+}
+
+impl PlannedFile {
+    /// Describe the operation, e.g. `Created empty input file "data/inputs/01.txt"`.
+    fn describe(&self, dry_run: bool) -> String {
+        let verb = match (self.action, dry_run) {
+            (Action::Create, false) => "Created",
+            (Action::Create, true) => "Would create",
+            (Action::Overwrite, false) => "Overwrote",
+            (Action::Overwrite, true) => "Would overwrite",
+            (Action::Keep, false) => "Kept existing",
+            (Action::Keep, true) => "Would keep existing",
+        };
+        let empty = if self.contents.is_empty() && self.action == Action::Create {
+            "empty "
+        } else {
+            ""
+        };
+        format!(
+            "{verb} {empty}{} file \"{}\"",
+            self.kind,
+            self.path.display()
+        )
+    }
+}
+
+/// Decide what to do with each file, without touching the file system apart from checking which files exist.
+pub fn plan(files: Vec<(FileKind, PathBuf, String)>, overwrite: &Overwrite) -> Vec<PlannedFile> {
+    files
+        .into_iter()
+        .map(|(kind, path, contents)| {
+            let action = match (path.exists(), overwrite.allows(kind)) {
+                (false, _) => Action::Create,
+                (true, true) => Action::Overwrite,
+                (true, false) => Action::Keep,
+            };
+            PlannedFile {
+                kind,
+                path,
+                contents,
+                action,
+            }
+        })
+        .collect()
+}
+
+/// Carry out a single planned file operation. New files are created with create-new semantics, so a file that
+/// appeared after planning is never replaced.
+pub fn execute(file: &PlannedFile) -> Result<(), Error> {
+    let mut options = OpenOptions::new();
+    match file.action {
+        Action::Create => options.write(true).create_new(true),
+        Action::Overwrite => options.write(true).truncate(true),
+        Action::Keep => return Ok(()),
+    };
+    options
+        .open(&file.path)
+        .and_then(|mut f| f.write_all(file.contents.as_bytes()))
+        .map_err(|source| Error::File {
+            path: file.path.clone(),
+            source,
+        })
+}
+
+pub fn handle(
+    day: Day,
+    overwrite: &Overwrite,
+    dry_run: bool,
+    template: &TemplateOptions,
+) -> Result<(), Error> {
+    let config = Config::get();
+
+    let module = read_template(&template.name).map_err(Error::Template)?;
+    let module = render_template(&module, day, config.year(), template);
+
+    let files = plan(
+        vec![
+            (FileKind::Module, config.bin_path(day), module),
+            (FileKind::Input, config.input_path(day), String::new()),
+            (FileKind::Example, config.example_path(day), String::new()),
+        ],
+        overwrite,
+    );
+
+    for file in &files {
+        if !dry_run {
+            execute(file)?;
         }
-        Err(e) => {
-            eprintln!("Failed to create example file: {e}");
-            process::exit(1);
+
+        if file.action == Action::Keep {
+            println!(
+                "{} (pass `--overwrite={}` to replace it)",
+                file.describe(dry_run),
+                file.kind
+            );
+        } else {
+            println!("{}", file.describe(dry_run));
         }
     }
 
-    println!("---");
-    println!("🎄 Type `cargo solve {day}` to run your solution.");
+    if !dry_run {
+        println!("---");
+        println!("🎄 Type `cargo solve {day}` to run your solution.");
+    }
+
+    Ok(())
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use super::{
+        execute, handle, plan, render_template, Action, FileKind, Overwrite, PlannedFile,
+        TemplateOptions, MODULE_TEMPLATE,
+    };
+    use crate::day;
+    use crate::template::Error;
+
+    /// Creates an empty scratch directory for a single test.
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc-scaffold-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Plans the module, input and example files of a day in `dir`.
+    fn plan_in(dir: &PathBuf, overwrite: &Overwrite) -> Vec<PlannedFile> {
+        plan(
+            vec![
+                (FileKind::Module, dir.join("01.rs"), "fn main() {}".into()),
+                (FileKind::Input, dir.join("input.txt"), String::new()),
+                (FileKind::Example, dir.join("example.txt"), String::new()),
+            ],
+            overwrite,
+        )
+    }
+
+    fn actions(files: &[PlannedFile]) -> Vec<Action> {
+        files.iter().map(|f| f.action).collect()
+    }
+
+    #[test]
+    fn creates_missing_files() {
+        let dir = scratch_dir("create");
+        let files = plan_in(&dir, &Overwrite::default());
+        assert_eq!(actions(&files), [Action::Create; 3]);
+
+        files.iter().for_each(|f| execute(f).unwrap());
+        assert_eq!(
+            fs::read_to_string(dir.join("01.rs")).unwrap(),
+            "fn main() {}"
+        );
+        assert_eq!(fs::read_to_string(dir.join("input.txt")).unwrap(), "");
+    }
+
+    #[test]
+    fn keeps_existing_files() {
+        let dir = scratch_dir("keep");
+        fs::write(dir.join("01.rs"), "// solved").unwrap();
+        fs::write(dir.join("input.txt"), "1 2 3").unwrap();
+
+        let files = plan_in(&dir, &Overwrite::default());
+        assert_eq!(
+            actions(&files),
+            [Action::Keep, Action::Keep, Action::Create]
+        );
+
+        files.iter().for_each(|f| execute(f).unwrap());
+        assert_eq!(fs::read_to_string(dir.join("01.rs")).unwrap(), "// solved");
+        assert_eq!(fs::read_to_string(dir.join("input.txt")).unwrap(), "1 2 3");
+    }
+
+    #[test]
+    fn overwrites_selected_files() {
+        let dir = scratch_dir("overwrite");
+        fs::write(dir.join("01.rs"), "// solved").unwrap();
+        fs::write(dir.join("input.txt"), "1 2 3").unwrap();
+        fs::write(dir.join("example.txt"), "1").unwrap();
+
+        let files = plan_in(&dir, &"module,examples".parse().unwrap());
+        assert_eq!(
+            actions(&files),
+            [Action::Overwrite, Action::Keep, Action::Overwrite]
+        );
+
+        files.iter().for_each(|f| execute(f).unwrap());
+        assert_eq!(
+            fs::read_to_string(dir.join("01.rs")).unwrap(),
+            "fn main() {}"
+        );
+        assert_eq!(fs::read_to_string(dir.join("input.txt")).unwrap(), "1 2 3");
+        assert_eq!(fs::read_to_string(dir.join("example.txt")).unwrap(), "");
+    }
+
+    #[test]
+    fn never_replaces_files_created_after_planning() {
+        let dir = scratch_dir("race");
+        let files = plan_in(&dir, &Overwrite::default());
+        fs::write(dir.join("input.txt"), "1 2 3").unwrap();
+
+        assert!(execute(&files[1]).is_err());
+        assert_eq!(fs::read_to_string(dir.join("input.txt")).unwrap(), "1 2 3");
+    }
+
+    #[test]
+    fn describes_dry_run() {
+        let dir = scratch_dir("dry-run");
+        fs::write(dir.join("input.txt"), "1 2 3").unwrap();
+
+        let files = plan_in(&dir, &Overwrite::default());
+        assert!(files[0]
+            .describe(true)
+            .starts_with("Would create module file"));
+        assert!(files[1]
+            .describe(true)
+            .starts_with("Would keep existing input file"));
+        assert!(files[2]
+            .describe(true)
+            .starts_with("Would create empty example file"));
+        assert!(!dir.join("01.rs").exists());
+    }
+
+    #[test]
+    fn parses_overwrite_choices() {
+        assert_eq!("".parse::<Overwrite>().is_err(), true);
+        assert_eq!(
+            "all".parse::<Overwrite>().unwrap(),
+            Overwrite {
+                module: true,
+                input: true,
+                example: true
+            }
+        );
+        assert_eq!(
+            "inputs".parse::<Overwrite>().unwrap(),
+            Overwrite {
+                input: true,
+                ..Overwrite::default()
+            }
+        );
+        assert!("puzzles".parse::<Overwrite>().is_err());
+    }
+
+    #[test]
+    fn renders_default_template() {
+        let module = render_template(MODULE_TEMPLATE, day!(4), None, &TemplateOptions::default());
+        assert!(module.starts_with("advent_of_code::solution!(4);\n\npub fn part_one"));
+        assert!(module.contains("pub fn part_two(input: &str) -> Option<u32>"));
+        assert!(!module.contains('%'));
+    }
+
+    #[test]
+    fn renders_placeholders() {
+        let template = "// %YEAR%/day/%DAY_NUMBER%\n%IMPORTS%\nadvent_of_code::solution!(%SOLUTION_ARGS%);\nfn f() -> %RETURN_TYPE% {}\n";
+        let options = TemplateOptions {
+            return_type: "u64".into(),
+            imports: vec!["Cardinal".into(), "VecExt".into()],
+            ..TemplateOptions::default()
+        };
+        assert_eq!(
+            render_template(template, day!(12), Some(2024), &options),
+            "// 2024/day/12\nuse advent_of_code::{Cardinal, VecExt};\nadvent_of_code::solution!(12);\nfn f() -> u64 {}\n"
+        );
+    }
+
+    #[test]
+    fn skips_imports_of_the_template() {
+        let template = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/grid.txt"));
+        let options = TemplateOptions {
+            imports: vec!["Cardinal".into(), "VecExt".into()],
+            ..TemplateOptions::default()
+        };
+        let module = render_template(template, day!(6), Some(2024), &options);
+        assert_eq!(module.matches("Cardinal;").count(), 1);
+        assert!(module.contains("use advent_of_code::VecExt;\n"));
+        assert!(module.starts_with("// https://adventofcode.com/2024/day/6\n"));
+
+        let options = TemplateOptions {
+            imports: vec!["Cardinal".into()],
+            ..TemplateOptions::default()
+        };
+        let module = render_template(template, day!(6), None, &options);
+        assert!(module.starts_with("use advent_of_code::Cardinal;\nuse numeric"));
+    }
+
+    #[test]
+    fn removes_empty_imports() {
+        let module = render_template(
+            "use std::fs;\n%IMPORTS%\n\nfn main() {}",
+            day!(1),
+            None,
+            &TemplateOptions::default(),
+        );
+        assert_eq!(module, "use std::fs;\n\nfn main() {}");
+    }
+
+    #[test]
+    fn renders_part_one_only() {
+        let options = TemplateOptions {
+            part_one_only: true,
+            ..TemplateOptions::default()
+        };
+        let module = render_template(MODULE_TEMPLATE, day!(4), None, &options);
+        assert!(module.starts_with("advent_of_code::solution!(4, 1);"));
+        assert!(module.contains("fn test_part_one"));
+        assert!(!module.contains("part_two"));
+        assert!(!module.contains('%'));
+    }
+
+    #[test]
+    fn fails_for_unknown_templates() {
+        let template = TemplateOptions {
+            name: "does-not-exist".into(),
+            ..TemplateOptions::default()
+        };
+        let err = handle(day!(1), &Overwrite::default(), true, &template).unwrap_err();
+        assert!(matches!(err, Error::Template(_)));
+    }
+
+    #[test]
+    fn fails_for_missing_folders() {
+        let dir = scratch_dir("missing").join("missing");
+        let files = plan_in(&dir, &Overwrite::default());
+        let err = execute(&files[0]).unwrap_err();
+        assert!(matches!(err, Error::File { path, .. } if path == dir.join("01.rs")));
+    }
 }
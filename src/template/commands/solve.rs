@@ -1,8 +1,16 @@
 use std::process::{Command, Stdio};
 
-use crate::template::Day;
+use crate::template::error::{describe_command, run_command};
+use crate::template::run_multi::child_commands::wait_with_timeout;
+use crate::template::{Config, Day, Error};
 
-pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
+pub fn handle(
+    day: Day,
+    release: bool,
+    dhat: bool,
+    submit_part: Option<u8>,
+    bin_args: &[String],
+) -> Result<(), Error> {
     let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
 
     if dhat {
@@ -23,12 +31,37 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
         cmd_args.push(submit_part.to_string());
     }
 
-    let mut cmd = Command::new("cargo")
+    cmd_args.extend_from_slice(bin_args);
+
+    let timeout = Config::get().run.timeout;
+
+    if timeout.is_some() {
+        // build separately so compilation does not count towards the timeout.
+        let separator = cmd_args.iter().position(|arg| arg == "--").unwrap();
+        run_command(
+            Command::new("cargo")
+                .arg("build")
+                .args(&cmd_args[1..separator]),
+        )?;
+    }
+
+    let mut command = Command::new("cargo");
+    command
         .args(&cmd_args)
         .stdout(Stdio::inherit())
-        .stderr(Stdio::inherit())
-        .spawn()
-        .unwrap();
+        .stderr(Stdio::inherit());
+
+    let mut cmd = command.spawn().map_err(|source| Error::Command {
+        command: describe_command(&command),
+        source,
+    })?;
 
-    cmd.wait().unwrap();
+    match wait_with_timeout(&mut cmd, timeout)? {
+        Some(status) if status.success() => Ok(()),
+        Some(status) => Err(Error::CommandFailed {
+            command: describe_command(&command),
+            status,
+        }),
+        None => Err(Error::Timeout(timeout.unwrap_or_default())),
+    }
 }
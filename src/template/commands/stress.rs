@@ -0,0 +1,215 @@
+/// Compares a solution with a reference implementation on random inputs from [`crate::gen`], and shrinks the first
+/// input they disagree on.
+///
+/// The reference is any executable with the interface of a solution binary, e.g. a solution built from another
+/// branch: it is run with `--input <path> --format json`.
+use std::{
+    collections::HashMap,
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    thread,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use tinyjson::JsonValue;
+
+use crate::gen::{self, Generated, Rng};
+use crate::template::corpus::parse_results;
+use crate::template::error::describe_command;
+use crate::template::run_multi::child_commands::wait_with_timeout;
+use crate::template::{Config, Day, Error};
+
+/// The results of a run, or why there are none.
+type RunResult = Result<Vec<(u8, Option<String>)>, String>;
+
+pub fn handle(
+    day: Day,
+    against: &Path,
+    seed: Option<u64>,
+    runs: u64,
+    size: usize,
+    release: bool,
+) -> Result<(), Error> {
+    let generate = gen::generator(day).ok_or(Error::NoGenerator(day))?;
+
+    if !against.is_file() {
+        return Err(Error::File {
+            path: against.to_path_buf(),
+            source: std::io::ErrorKind::NotFound.into(),
+        });
+    }
+    let solution = build_solution(day, release)?;
+
+    let seed = seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_secs())
+    });
+    println!(
+        "Comparing day {day} with \"{}\" on {runs} random inputs (seed {seed}).",
+        against.display()
+    );
+
+    let input_path =
+        std::env::temp_dir().join(format!("aoc-stress-{day}-{}.txt", std::process::id()));
+
+    for case_seed in (0..runs).map(|i| seed.wrapping_add(i)) {
+        let mut rng = Rng::new(case_seed);
+        let size = rng.range(1..=size.max(1) as u64) as usize;
+        let generated = generate(&mut rng, size);
+
+        let disagree = |input: &Generated| -> Result<Option<(RunResult, RunResult)>, Error> {
+            write_input(&input_path, &input.render())?;
+            let ours = run(&solution, &input_path)?;
+            let theirs = run(against, &input_path)?;
+            Ok((!agree(&ours, &theirs)).then_some((ours, theirs)))
+        };
+
+        if disagree(&generated)?.is_none() {
+            continue;
+        }
+
+        println!("Found an input the solutions disagree on (seed {case_seed}), shrinking it.");
+        let mut error = None;
+        let items = gen::shrink(generated.items.clone(), |items| {
+            match disagree(&generated.with_items(items.to_vec())) {
+                Ok(result) => result.is_some(),
+                Err(e) => {
+                    error.get_or_insert(e);
+                    false
+                }
+            }
+        });
+        if let Some(e) = error {
+            return Err(e);
+        }
+
+        let shrunk = generated.with_items(items);
+        let (ours, theirs) = disagree(&shrunk)?.expect("shrunk input still fails");
+        let _ = fs::remove_file(&input_path);
+
+        let path = Config::get()
+            .data_folder("stress")
+            .join(format!("{day}-{case_seed}.txt"));
+        write_input(&path, &shrunk.render())?;
+
+        println!("Solution:  {}", describe(&ours));
+        println!("Reference: {}", describe(&theirs));
+        return Err(Error::StressFailed {
+            seed: case_seed,
+            input: path,
+        });
+    }
+
+    let _ = fs::remove_file(&input_path);
+    println!("All {runs} inputs gave the same results.");
+    Ok(())
+}
+
+/// Build the solution once, so every run skips cargo. Returns the path of the executable.
+fn build_solution(day: Day, release: bool) -> Result<PathBuf, Error> {
+    let mut command = Command::new("cargo");
+    command
+        .args(["build", "--quiet", "--bin", &day.to_string()])
+        .arg("--message-format=json-render-diagnostics")
+        .stderr(Stdio::inherit());
+    if release {
+        command.arg("--release");
+    }
+
+    let output = command.output().map_err(|source| Error::Command {
+        command: describe_command(&command),
+        source,
+    })?;
+    if !output.status.success() {
+        return Err(Error::CommandFailed {
+            command: describe_command(&command),
+            status: output.status,
+        });
+    }
+
+    let executable = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .rev()
+        .filter_map(|line| line.parse::<JsonValue>().ok())
+        .find_map(|json| {
+            let object = json.get::<HashMap<String, JsonValue>>()?;
+            object.get("executable")?.get::<String>().cloned()
+        });
+
+    executable.map(PathBuf::from).ok_or_else(|| Error::Command {
+        command: describe_command(&command),
+        source: std::io::Error::other("cargo did not report an executable"),
+    })
+}
+
+fn write_input(path: &Path, input: &str) -> Result<(), Error> {
+    let write = || {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, input)
+    };
+    write().map_err(|source| Error::File {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+fn run(executable: &Path, input: &Path) -> Result<RunResult, Error> {
+    let mut command = Command::new(executable);
+    command
+        .arg("--input")
+        .arg(input)
+        .args(["--format", "json"])
+        .stdout(Stdio::piped())
+        // panics are expected while shrinking, don't print them.
+        .stderr(Stdio::null());
+
+    let mut child = command.spawn().map_err(|source| Error::Command {
+        command: describe_command(&command),
+        source,
+    })?;
+
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let reader = thread::spawn(move || {
+        let mut output = String::new();
+        let _ = stdout.read_to_string(&mut output);
+        output
+    });
+
+    let status = wait_with_timeout(&mut child, Config::get().run.timeout)?;
+    let output: Vec<String> = reader.join().unwrap().lines().map(String::from).collect();
+
+    let Some(status) = status else {
+        return Ok(Err("timed out".into()));
+    };
+
+    let results = parse_results(&output);
+    if results.is_empty() {
+        return Ok(Err(format!("no results, exited with {status}")));
+    }
+    Ok(Ok(results))
+}
+
+/// Two runs agree if they have the same results, or if both failed.
+fn agree(a: &RunResult, b: &RunResult) -> bool {
+    match (a, b) {
+        (Ok(a), Ok(b)) => a == b,
+        (Err(_), Err(_)) => true,
+        _ => false,
+    }
+}
+
+fn describe(result: &RunResult) -> String {
+    match result {
+        Ok(results) => results
+            .iter()
+            .map(|(part, result)| format!("part {part}: {}", result.as_deref().unwrap_or("✖")))
+            .collect::<Vec<_>>()
+            .join(", "),
+        Err(e) => e.clone(),
+    }
+}
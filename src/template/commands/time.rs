@@ -2,9 +2,9 @@ use std::collections::HashSet;
 
 use crate::template::run_multi::run_multi;
 use crate::template::timings::Timings;
-use crate::template::{all_days, readme_benchmarks, Day};
+use crate::template::{all_days, readme_benchmarks, Config, Day, Error};
 
-pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
+pub fn handle(day: Option<Day>, run_all: bool, store: bool) -> Result<(), Error> {
     let stored_timings = Timings::read_from_file();
 
     let days_to_run = day.map_or_else(
@@ -21,20 +21,19 @@ pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
         |day| HashSet::from([day]),
     );
 
-    let timings = run_multi(&days_to_run, true, true).unwrap();
+    let timings = run_multi(&days_to_run, true, true, None)?.unwrap_or_default();
 
     if store {
         let merged_timings = stored_timings.merge(&timings);
-        merged_timings.store_file().unwrap();
+        merged_timings.store_file().map_err(|source| Error::File {
+            path: Config::get().paths.timings.clone(),
+            source,
+        })?;
 
         println!();
-        match readme_benchmarks::update(merged_timings) {
-            Ok(()) => {
-                println!("Stored updated benchmarks.");
-            }
-            Err(_) => {
-                eprintln!("Failed to store updated benchmarks.");
-            }
-        }
+        readme_benchmarks::update(merged_timings)?;
+        println!("Stored updated benchmarks.");
     }
+
+    Ok(())
 }
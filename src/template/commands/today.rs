@@ -0,0 +1,28 @@
+use crate::template::commands::{download, read, scaffold};
+use crate::template::{Day, Error};
+
+/// Scaffold, download and read the puzzle of `today`, which is `None` outside of advent.
+pub fn handle(today: Option<Day>) -> Result<(), Error> {
+    let day = today.ok_or(Error::NotAdventDay)?;
+    scaffold::handle(day, &Default::default(), false, &Default::default())?;
+    download::handle(day, false)?;
+    read::handle(day)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::handle;
+    use crate::template::Error;
+
+    #[test]
+    fn fails_outside_of_advent() {
+        let err = handle(None).unwrap_err();
+        assert!(matches!(err, Error::NotAdventDay));
+        assert_eq!(err.exit_code(), 1);
+        assert!(err
+            .to_string()
+            .contains("use `scaffold` with a specific day"));
+    }
+}
@@ -0,0 +1,351 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    str::FromStr,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use tinyjson::JsonValue;
+
+use crate::template::run_multi::child_commands::wait_with_timeout;
+use crate::template::{Config, Day, Error, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Modification times of all files that affect a day.
+type Snapshot = Vec<(PathBuf, SystemTime)>;
+
+pub fn handle(day: Day, release: bool) -> Result<(), Error> {
+    let module_path = Config::get().bin_path(day);
+    if !module_path.exists() {
+        return Err(Error::File {
+            path: module_path,
+            source: std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("no solution found, run `cargo scaffold {day}` first"),
+            ),
+        });
+    }
+
+    println!("👀 Watching day {day}. Press Ctrl-C to stop.");
+
+    let mut last = snapshot(day);
+    run_once(day, release);
+
+    loop {
+        thread::sleep(POLL_INTERVAL);
+        let current = snapshot(day);
+        if current != last {
+            last = current;
+            run_once(day, release);
+        }
+    }
+}
+
+/// The files that trigger a rerun: the solution, the library sources, and the day's inputs and examples with their
+/// answers and params.
+fn watched_files(day: Day) -> Vec<PathBuf> {
+    let config = Config::get();
+    let mut files = vec![config.bin_path(day)];
+
+    let lib_dir = config.paths.bins.parent().unwrap_or(Path::new("src"));
+    files.extend(list_files(lib_dir, |name| name.ends_with(".rs")));
+
+    for folder in [&config.paths.inputs, &config.paths.examples] {
+        files.extend(list_files(folder, |name| is_data_file(day, name)));
+    }
+
+    files
+}
+
+/// Whether a file in the inputs or examples folder belongs to `day`, e.g. `14-2.txt` or `14.params`.
+fn is_data_file(day: Day, name: &str) -> bool {
+    name.starts_with(&day.to_string())
+        && [".txt", ".answers", ".params"]
+            .iter()
+            .any(|extension| name.ends_with(extension))
+}
+
+fn list_files(dir: &Path, filter: impl Fn(&str) -> bool) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_file()))
+        .filter(|entry| entry.file_name().to_str().is_some_and(&filter))
+        .map(|entry| entry.path())
+        .collect()
+}
+
+fn snapshot(day: Day) -> Snapshot {
+    let mut snapshot: Snapshot = watched_files(day)
+        .into_iter()
+        .filter_map(|path| {
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            Some((path, modified))
+        })
+        .collect();
+    snapshot.sort();
+    snapshot
+}
+
+fn run_once(day: Day, release: bool) {
+    println!();
+    println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
+    println!("------");
+
+    let day_padded = day.to_string();
+    let mut args = vec!["--quiet", "--bin", &day_padded];
+    if release {
+        args.push("--release");
+    }
+
+    let tests = Command::new("cargo").arg("test").args(&args).output();
+
+    match tests {
+        Ok(output) => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            match parse_test_summary(&stdout) {
+                Some(summary) => print_test_summary(&summary),
+                None => {
+                    // most likely a compilation error, show it in full.
+                    eprint!("{}", String::from_utf8_lossy(&output.stderr));
+                    println!("Build failed.");
+                    return;
+                }
+            }
+        }
+        Err(e) => {
+            eprintln!("Failed to run tests: {e}");
+            return;
+        }
+    }
+
+    match run_solution(&args) {
+        Ok(Some(results)) if results.is_empty() => println!("No results."),
+        Ok(Some(results)) => results.iter().for_each(print_part_result),
+        Ok(None) => println!("Timed out."),
+        Err(e) => eprintln!("Failed to run solution: {e}"),
+    }
+}
+
+/// Aggregated results of a `cargo test` run.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct TestSummary {
+    passed: u32,
+    failed: u32,
+    failures: Vec<String>,
+}
+
+/// Parses the `test result: ...` lines of a `cargo test` run, and the names of failed tests from the `failures:` list
+/// that ends each failing run. Returns `None` if no tests were run.
+fn parse_test_summary(output: &str) -> Option<TestSummary> {
+    let mut summary = TestSummary::default();
+    let mut found = false;
+    // Whether the lines are in a `failures:` block. The list of names is the one that only has indented lines.
+    let mut in_failures = false;
+
+    for line in output.lines() {
+        if line == "failures:" {
+            in_failures = true;
+        } else if line.is_empty() {
+            continue;
+        } else if let Some(name) = line
+            .strip_prefix("    ")
+            .filter(|name| in_failures && !name.starts_with(' '))
+        {
+            summary.failures.push(name.to_string());
+        } else {
+            in_failures = false;
+        }
+
+        if let Some(result) = line.strip_prefix("test result: ") {
+            found = true;
+            for part in result.split(';') {
+                let mut words = part.split_whitespace().rev();
+                let (Some(label), Some(count)) = (words.next(), words.next()) else {
+                    continue;
+                };
+                match (label, u32::from_str(count)) {
+                    ("passed", Ok(count)) => summary.passed += count,
+                    ("failed", Ok(count)) => summary.failed += count,
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    found.then_some(summary)
+}
+
+fn print_test_summary(summary: &TestSummary) {
+    if summary.failed == 0 {
+        println!("Tests: {} passed ✔", summary.passed);
+    } else {
+        println!(
+            "Tests: {} passed, {ANSI_BOLD}{} failed{ANSI_RESET} ✖",
+            summary.passed, summary.failed
+        );
+        for failure in &summary.failures {
+            println!("  ✖ {failure}");
+        }
+    }
+}
+
+/// Runs the solution with JSON output and returns one object per part, or `None` if it timed out. With a timeout, the
+/// solution is built first so that only the run counts towards it.
+fn run_solution(args: &[&str]) -> std::io::Result<Option<Vec<HashMap<String, JsonValue>>>> {
+    let timeout = Config::get().run.timeout;
+    if timeout.is_some() {
+        // build separately so compilation does not count towards the timeout.
+        let status = Command::new("cargo").arg("build").args(args).status()?;
+        if !status.success() {
+            return Err(std::io::Error::other(format!(
+                "`cargo build` exited with {status}"
+            )));
+        }
+    }
+
+    let mut cmd = Command::new("cargo")
+        .arg("run")
+        .args(args)
+        .args(["--", "--format", "json"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()?;
+
+    let mut stdout = cmd.stdout.take().expect("stdout is piped");
+    let reader = thread::spawn(move || {
+        let mut output = String::new();
+        let _ = stdout.read_to_string(&mut output);
+        output
+    });
+
+    let status = wait_with_timeout(&mut cmd, timeout)?;
+    let output = reader.join().unwrap();
+
+    let Some(status) = status else {
+        return Ok(None);
+    };
+
+    let results: Vec<_> = output
+        .lines()
+        .filter_map(|line| JsonValue::from_str(line).ok())
+        .filter_map(|json| json.get::<HashMap<String, JsonValue>>().cloned())
+        .collect();
+
+    if results.is_empty() && !status.success() {
+        return Err(std::io::Error::other(format!(
+            "solution exited with {status}"
+        )));
+    }
+
+    Ok(Some(results))
+}
+
+fn print_part_result(result: &HashMap<String, JsonValue>) {
+    let part = result
+        .get("part")
+        .and_then(|v| v.get::<f64>())
+        .map_or_else(|| "?".into(), |p| p.to_string());
+    let nanos = result.get("nanos").and_then(|v| v.get::<f64>()).copied();
+
+    let answer = match result.get("result").and_then(|v| v.get::<String>()) {
+        Some(answer) if answer.contains('\n') => format!("▼\n{answer}"),
+        Some(answer) => format!("{ANSI_BOLD}{answer}{ANSI_RESET}"),
+        None => "✖".into(),
+    };
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let duration = nanos
+        .map(|n| {
+            format!(
+                " {ANSI_ITALIC}({:.1?}){ANSI_RESET}",
+                Duration::from_nanos(n as u64)
+            )
+        })
+        .unwrap_or_default();
+
+    println!("Part {part}: {answer}{duration}");
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{is_data_file, parse_test_summary, TestSummary};
+    use crate::template::Day;
+
+    #[test]
+    fn parses_passing_tests() {
+        let output = "\nrunning 2 tests\n..\ntest result: ok. 2 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.00s\n";
+        assert_eq!(
+            parse_test_summary(output),
+            Some(TestSummary {
+                passed: 2,
+                failed: 0,
+                failures: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn parses_failing_tests() {
+        // The output of `cargo test --quiet`, without most of the backtrace
+        let output = "
+running 3 tests
+.. 2/3
+tests::test_part_two --- FAILED
+
+failures:
+
+---- tests::test_part_two stdout ----
+
+thread 'tests::test_part_two' (3126) panicked at src/bin/10.rs:119:9:
+assertion `left == right` failed
+  left: Some(81)
+ right: None
+stack backtrace:
+   0: __rustc::rust_begin_unwind
+             at /rustc/59807616e1fa2540724bfbac14d7976d7e4a3860/library/std/src/panicking.rs:689:5
+note: Some details are omitted, run with `RUST_BACKTRACE=full` for a verbose backtrace.
+
+
+failures:
+    tests::test_part_two
+
+test result: FAILED. 2 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.02s
+
+";
+        assert_eq!(
+            parse_test_summary(output),
+            Some(TestSummary {
+                passed: 2,
+                failed: 1,
+                failures: vec!["tests::test_part_two".into()],
+            })
+        );
+    }
+
+    #[test]
+    fn watches_data_files_of_the_day() {
+        let day = Day::new(14).unwrap();
+        for name in ["14.txt", "14-2.txt", "14.answers", "14-2.params"] {
+            assert!(is_data_file(day, name), "{name}");
+        }
+        for name in ["15.txt", "14.md", "14.txt.bak"] {
+            assert!(!is_data_file(day, name), "{name}");
+        }
+    }
+
+    #[test]
+    fn handles_missing_test_results() {
+        assert_eq!(parse_test_summary("error[E0425]: cannot find value"), None);
+    }
+}
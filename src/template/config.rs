@@ -0,0 +1,302 @@
+/// Project-wide configuration, read from an optional `.aoc.toml` in the project root.
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+    time::Duration,
+};
+
+use toml::Table;
+
+use crate::template::Day;
+
+static CONFIG_FILE_PATH: &str = ".aoc.toml";
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// Locations of the files the template reads and writes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Paths {
+    pub inputs: PathBuf,
+    pub examples: PathBuf,
+    pub puzzles: PathBuf,
+    pub timings: PathBuf,
+    pub bins: PathBuf,
+    pub readme: PathBuf,
+    /// Folder with named module templates for `cargo scaffold --template`.
+    pub templates: PathBuf,
+    /// Folder of the input cache, shared between projects.
+    pub cache: PathBuf,
+}
+
+/// Limits applied when benching a solution with `--time`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bench {
+    /// Approximate total time to spend benching a single part.
+    pub target_time: Duration,
+    pub min_iterations: u128,
+    pub max_iterations: u128,
+}
+
+/// Defaults for commands that invoke solution binaries.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Run {
+    /// Build solutions with `--release` even if the flag is not passed.
+    pub release: bool,
+    /// Kill a solution binary if it runs for longer than this.
+    pub timeout: Option<Duration>,
+}
+
+/// Configuration shared by every command. Missing keys fall back to the template defaults.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Config {
+    pub year: Option<u16>,
+    pub paths: Paths,
+    pub bench: Bench,
+    pub run: Run,
+}
+
+impl Default for Paths {
+    fn default() -> Self {
+        Self {
+            inputs: "data/inputs".into(),
+            examples: "data/examples".into(),
+            puzzles: "data/puzzles".into(),
+            timings: "data/timings.json".into(),
+            bins: "src/bin".into(),
+            readme: "README.md".into(),
+            templates: "templates".into(),
+            cache: default_cache_dir(),
+        }
+    }
+}
+
+/// `$XDG_CACHE_HOME/advent-of-code`, falling back to `~/.cache` and `%LOCALAPPDATA%`.
+fn default_cache_dir() -> PathBuf {
+    std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))
+        .or_else(|| std::env::var_os("LOCALAPPDATA").map(PathBuf::from))
+        .unwrap_or_else(|| "data/.cache".into())
+        .join("advent-of-code")
+}
+
+impl Default for Bench {
+    fn default() -> Self {
+        Self {
+            target_time: Duration::from_secs(1),
+            min_iterations: 10,
+            max_iterations: 10000,
+        }
+    }
+}
+
+impl Config {
+    /// Returns the project configuration, loading it on first use.
+    /// If the config file is missing, the defaults are used.
+    pub fn get() -> &'static Config {
+        CONFIG.get_or_init(|| match Config::read_from_file(CONFIG_FILE_PATH) {
+            Ok(config) => config,
+            Err(e) => {
+                eprintln!("Failed to read \"{CONFIG_FILE_PATH}\", using defaults: {e}");
+                Config::default()
+            }
+        })
+    }
+
+    /// Read the configuration from a TOML file. If not present, returns the defaults.
+    pub fn read_from_file(path: impl AsRef<Path>) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Config::try_from(contents.as_str()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    /// The year to pass to aoc-cli. Falls back to the `AOC_YEAR` environment variable.
+    pub fn year(&self) -> Option<u16> {
+        self.year
+            .or_else(|| std::env::var("AOC_YEAR").ok()?.parse().ok())
+    }
+
+    /// Resolves a `data` folder name as used by [`read_file`](crate::template::read_file).
+    pub fn data_folder(&self, folder: &str) -> PathBuf {
+        match folder {
+            "inputs" => self.paths.inputs.clone(),
+            "examples" => self.paths.examples.clone(),
+            "puzzles" => self.paths.puzzles.clone(),
+            folder => Path::new("data").join(folder),
+        }
+    }
+
+    pub fn input_path(&self, day: Day) -> PathBuf {
+        self.paths.inputs.join(format!("{day}.txt"))
+    }
+
+    pub fn example_path(&self, day: Day) -> PathBuf {
+        self.paths.examples.join(format!("{day}.txt"))
+    }
+
+    pub fn puzzle_path(&self, day: Day) -> PathBuf {
+        self.paths.puzzles.join(format!("{day}.md"))
+    }
+
+    pub fn bin_path(&self, day: Day) -> PathBuf {
+        self.paths.bins.join(format!("{day}.rs"))
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl TryFrom<&str> for Config {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let toml = value.parse::<Table>().map_err(|e| e.to_string())?;
+        let mut config = Config::default();
+
+        if let Some(year) = toml.get("year") {
+            let year = year
+                .as_integer()
+                .and_then(|y| u16::try_from(y).ok())
+                .ok_or("expected `year` to be a number.")?;
+            config.year = Some(year);
+        }
+
+        if let Some(paths) = get_table(&toml, "paths")? {
+            let p = &mut config.paths;
+            for (key, field) in [
+                ("inputs", &mut p.inputs),
+                ("examples", &mut p.examples),
+                ("puzzles", &mut p.puzzles),
+                ("timings", &mut p.timings),
+                ("bins", &mut p.bins),
+                ("readme", &mut p.readme),
+                ("templates", &mut p.templates),
+                ("cache", &mut p.cache),
+            ] {
+                if let Some(path) = get_str(paths, "paths", key)? {
+                    *field = path.into();
+                }
+            }
+        }
+
+        if let Some(bench) = get_table(&toml, "bench")? {
+            if let Some(ms) = get_uint(bench, "bench", "target_time_ms")? {
+                config.bench.target_time = Duration::from_millis(ms);
+            }
+            if let Some(min) = get_uint(bench, "bench", "min_iterations")? {
+                config.bench.min_iterations = min.into();
+            }
+            if let Some(max) = get_uint(bench, "bench", "max_iterations")? {
+                config.bench.max_iterations = max.into();
+            }
+            if config.bench.min_iterations == 0
+                || config.bench.min_iterations > config.bench.max_iterations
+            {
+                return Err(
+                    "expected `bench.min_iterations` to be between 1 and `bench.max_iterations`."
+                        .into(),
+                );
+            }
+        }
+
+        if let Some(run) = get_table(&toml, "run")? {
+            if let Some(release) = run.get("release") {
+                config.run.release = release
+                    .as_bool()
+                    .ok_or("expected `run.release` to be a boolean.")?;
+            }
+            if let Some(secs) = get_uint(run, "run", "timeout_secs")? {
+                config.run.timeout = Some(Duration::from_secs(secs));
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+fn get_table<'a>(toml: &'a Table, key: &str) -> Result<Option<&'a Table>, String> {
+    toml.get(key)
+        .map(|v| {
+            v.as_table()
+                .ok_or_else(|| format!("expected `{key}` to be a table."))
+        })
+        .transpose()
+}
+
+fn get_str<'a>(table: &'a Table, section: &str, key: &str) -> Result<Option<&'a str>, String> {
+    table
+        .get(key)
+        .map(|v| {
+            v.as_str()
+                .ok_or_else(|| format!("expected `{section}.{key}` to be a string."))
+        })
+        .transpose()
+}
+
+fn get_uint(table: &Table, section: &str, key: &str) -> Result<Option<u64>, String> {
+    table
+        .get(key)
+        .map(|v| {
+            v.as_integer()
+                .and_then(|i| u64::try_from(i).ok())
+                .ok_or_else(|| format!("expected `{section}.{key}` to be a positive number."))
+        })
+        .transpose()
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::Duration;
+
+    use super::Config;
+
+    #[test]
+    fn handles_empty_config() {
+        let config = Config::try_from("").unwrap();
+        assert_eq!(config, Config::default());
+    }
+
+    #[test]
+    fn handles_partial_config() {
+        let config = Config::try_from(
+            r#"
+            year = 2023
+
+            [paths]
+            inputs = "inputs"
+
+            [bench]
+            max_iterations = 50
+
+            [run]
+            release = true
+            timeout_secs = 30
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.year, Some(2023));
+        assert_eq!(config.paths.inputs.to_str(), Some("inputs"));
+        assert_eq!(config.paths.examples.to_str(), Some("data/examples"));
+        assert_eq!(config.bench.min_iterations, 10);
+        assert_eq!(config.bench.max_iterations, 50);
+        assert_eq!(config.run.release, true);
+        assert_eq!(config.run.timeout, Some(Duration::from_secs(30)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_invalid_types() {
+        Config::try_from("[run]\nrelease = \"yes\"").unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_invalid_iteration_bounds() {
+        Config::try_from("[bench]\nmin_iterations = 100\nmax_iterations = 10").unwrap();
+    }
+}
@@ -0,0 +1,158 @@
+/// Per-run parameters for solutions that behave differently on the examples than on the real input,
+/// e.g. the size of a grid that is not part of the input itself.
+///
+/// Parameters are read from a params file next to the input (e.g. `data/examples/14.params`):
+///
+/// ```toml
+/// width = 11
+/// height = 7
+/// ```
+///
+/// If there is no params file, the context's [`Default`] is used, which should describe the real input.
+use std::{fs, path::Path};
+
+use toml::{Table, Value};
+
+/// A typed context that is passed to each part of a solution declared with `solution!(DD, ctx: Type)`.
+pub trait Context: Default {
+    /// Build the context from the key-value pairs of a params file.
+    fn from_params(params: &Params) -> Result<Self, String>;
+}
+
+impl Context for () {
+    fn from_params(_: &Params) -> Result<Self, String> {
+        Ok(())
+    }
+}
+
+/// The contents of a params file.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Params(Table);
+
+impl Params {
+    /// Read a params file. If not present, returns `None`.
+    pub fn read_from_file(path: impl AsRef<Path>) -> Result<Option<Self>, String> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Params::try_from(contents.as_str()).map(Some),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    pub fn int<T: TryFrom<i64>>(&self, key: &str) -> Result<Option<T>, String> {
+        self.0
+            .get(key)
+            .map(|v| {
+                v.as_integer()
+                    .and_then(|i| T::try_from(i).ok())
+                    .ok_or_else(|| format!("expected `{key}` to be a number in range."))
+            })
+            .transpose()
+    }
+
+    pub fn str(&self, key: &str) -> Result<Option<&str>, String> {
+        self.0
+            .get(key)
+            .map(|v| {
+                v.as_str()
+                    .ok_or_else(|| format!("expected `{key}` to be a string."))
+            })
+            .transpose()
+    }
+
+    pub fn bool(&self, key: &str) -> Result<Option<bool>, String> {
+        self.0
+            .get(key)
+            .map(|v| {
+                v.as_bool()
+                    .ok_or_else(|| format!("expected `{key}` to be a boolean."))
+            })
+            .transpose()
+    }
+}
+
+/// Read the context for an input from its params file, falling back to the default if there is none.
+pub fn read_context_from_file<C: Context>(path: impl AsRef<Path>) -> Result<C, String> {
+    match Params::read_from_file(path)? {
+        Some(params) => C::from_params(&params),
+        None => Ok(C::default()),
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl TryFrom<&str> for Params {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let toml = value.parse::<Table>().map_err(|e| e.to_string())?;
+        if let Some((key, _)) = toml.iter().find(|(_, v)| matches!(v, Value::Table(_))) {
+            return Err(format!("expected `{key}` to be a value, not a table."));
+        }
+        Ok(Params(toml))
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Context, Params};
+
+    #[derive(Debug, PartialEq)]
+    struct Grid {
+        width: u32,
+        height: u32,
+    }
+
+    impl Default for Grid {
+        fn default() -> Self {
+            Grid {
+                width: 101,
+                height: 103,
+            }
+        }
+    }
+
+    impl Context for Grid {
+        fn from_params(params: &Params) -> Result<Self, String> {
+            let default = Grid::default();
+            Ok(Grid {
+                width: params.int("width")?.unwrap_or(default.width),
+                height: params.int("height")?.unwrap_or(default.height),
+            })
+        }
+    }
+
+    #[test]
+    fn parses_params() {
+        let params = Params::try_from("width = 11\nheight = 7").unwrap();
+        assert_eq!(
+            Grid::from_params(&params).unwrap(),
+            Grid {
+                width: 11,
+                height: 7
+            }
+        );
+    }
+
+    #[test]
+    fn falls_back_to_defaults() {
+        let params = Params::try_from("height = 7").unwrap();
+        assert_eq!(Grid::from_params(&params).unwrap().width, 101);
+        assert_eq!(params.str("name"), Ok(None));
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_out_of_range_values() {
+        let params = Params::try_from("width = -1").unwrap();
+        Grid::from_params(&params).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_nested_tables() {
+        Params::try_from("[grid]\nwidth = 11").unwrap();
+    }
+}
@@ -0,0 +1,347 @@
+/// Validates solutions against a corpus of inputs, e.g. inputs shared from several accounts, used by `cargo all --corpus`.
+///
+/// A corpus is a directory of inputs named after their day, like `data/corpus/01-alice.txt`. Each input can have an
+/// answers file next to it, like `data/corpus/01-alice.answers`, in the same format as the answers of examples.
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use tinyjson::JsonValue;
+
+use crate::template::answers::Answers;
+use crate::template::run_multi::{child_commands::run_bin, get_path_for_bin};
+use crate::template::{all_days, Day, Error, ANSI_BOLD, ANSI_RESET};
+
+/// An input in the corpus.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CorpusInput {
+    pub day: Day,
+    /// The name of the input without its day, e.g. `alice` for `01-alice.txt`.
+    pub name: String,
+    pub path: PathBuf,
+}
+
+impl CorpusInput {
+    pub fn answers_path(&self) -> PathBuf {
+        self.path.with_extension("answers")
+    }
+}
+
+/// The result of running a solution against one input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Passed,
+    /// At least one part did not match its answer, with a description per mismatching part.
+    Failed(Vec<String>),
+    /// There are no answers to compare the results to.
+    Unchecked,
+    /// The solution could not be run to completion.
+    Error(String),
+}
+
+impl Outcome {
+    fn symbol(&self) -> &'static str {
+        match self {
+            Outcome::Passed => "✔",
+            Outcome::Failed(_) => "✖",
+            Outcome::Unchecked => "?",
+            Outcome::Error(_) => "!",
+        }
+    }
+}
+
+/// Returns the inputs for a day in a corpus directory, sorted by name.
+pub fn find_inputs(dir: &Path, day: Day) -> io::Result<Vec<CorpusInput>> {
+    let prefix = day.to_string();
+    let mut inputs = vec![];
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "txt") {
+            continue;
+        }
+        let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        // `01-alice` belongs to day 1, but `011.txt` does not.
+        let Some(rest) = stem.strip_prefix(&prefix) else {
+            continue;
+        };
+        if rest.starts_with(|c: char| c.is_ascii_digit()) {
+            continue;
+        }
+
+        let name = rest.trim_start_matches(['-', '_', '.']);
+        inputs.push(CorpusInput {
+            day,
+            name: if name.is_empty() { "default" } else { name }.to_string(),
+            path,
+        });
+    }
+
+    inputs.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(inputs)
+}
+
+/// Parses the results of a solution run with `--format json`, as pairs of part and result.
+pub fn parse_results(output: &[String]) -> Vec<(u8, Option<String>)> {
+    output
+        .iter()
+        .filter_map(|line| line.parse::<JsonValue>().ok())
+        .filter_map(|json| {
+            let object = json.get::<HashMap<String, JsonValue>>()?;
+            let part = *object.get("part")?.get::<f64>()?;
+            let result = object.get("result")?.get::<String>().cloned();
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            Some((part as u8, result))
+        })
+        .collect()
+}
+
+/// Compare the results of a solution to the expected answers. Parts without an answer are not checked.
+pub fn compare(results: &[(u8, Option<String>)], answers: &Answers) -> Outcome {
+    if answers.part_one.is_none() && answers.part_two.is_none() {
+        return Outcome::Unchecked;
+    }
+
+    let mut failures = vec![];
+    for part in [1, 2] {
+        let Some(expected) = answers.get(part) else {
+            continue;
+        };
+        let actual = results
+            .iter()
+            .find(|(p, _)| *p == part)
+            .and_then(|(_, result)| result.as_deref());
+        if actual != Some(expected) {
+            failures.push(format!(
+                "part {part}: expected {expected}, got {}",
+                actual.unwrap_or("✖")
+            ));
+        }
+    }
+
+    if failures.is_empty() {
+        Outcome::Passed
+    } else {
+        Outcome::Failed(failures)
+    }
+}
+
+fn run_input(input: &CorpusInput, is_release: bool) -> Result<Outcome, Error> {
+    let answers = match Answers::read_from_file(input.answers_path()) {
+        Ok(answers) => answers,
+        Err(e) => return Ok(Outcome::Error(format!("invalid answers file: {e}"))),
+    };
+
+    let path = input.path.to_string_lossy();
+    let bin_args = ["--format", "json", "--input", &path];
+    let output = match run_bin(input.day, is_release, &bin_args, false) {
+        Err(Error::Timeout(timeout)) => {
+            return Ok(Outcome::Error(format!("timed out after {timeout:?}")))
+        }
+        res => res?,
+    };
+
+    let results = parse_results(&output);
+    if results.is_empty() {
+        return Ok(Outcome::Error(
+            "the solution did not print any results".into(),
+        ));
+    }
+
+    Ok(compare(&results, &answers))
+}
+
+/// Renders the outcomes as a matrix with a row per day and a column per input name.
+pub fn render_matrix(rows: &[(Day, Vec<(String, Outcome)>)]) -> String {
+    let names: BTreeSet<&str> = rows
+        .iter()
+        .flat_map(|(_, outcomes)| outcomes.iter().map(|(name, _)| name.as_str()))
+        .collect();
+
+    let mut matrix = format!("{ANSI_BOLD}Day{ANSI_RESET}");
+    for name in &names {
+        matrix.push_str(&format!(" | {ANSI_BOLD}{name}{ANSI_RESET}"));
+    }
+    matrix.push('\n');
+
+    for (day, outcomes) in rows {
+        matrix.push_str(&format!("{day} "));
+        for name in &names {
+            let symbol = outcomes
+                .iter()
+                .find(|(n, _)| n == name)
+                .map_or("-", |(_, outcome)| outcome.symbol());
+            let width = name.chars().count();
+            matrix.push_str(&format!(" | {symbol:<width$}"));
+        }
+        matrix.push('\n');
+    }
+
+    matrix
+}
+
+/// Run every day against all of its inputs in the corpus, print a matrix of the outcomes and fail if any input did
+/// not match its answers.
+pub fn run_corpus(dir: &Path, is_release: bool) -> Result<(), Error> {
+    let mut rows = vec![];
+
+    for day in all_days() {
+        let inputs = find_inputs(dir, day).map_err(|source| Error::File {
+            path: dir.to_path_buf(),
+            source,
+        })?;
+        if inputs.is_empty() {
+            continue;
+        }
+
+        let mut outcomes = vec![];
+        for input in inputs {
+            let outcome = if Path::new(&get_path_for_bin(day)).exists() {
+                run_input(&input, is_release)?
+            } else {
+                Outcome::Error("not solved".into())
+            };
+            outcomes.push((input.name, outcome));
+        }
+        rows.push((day, outcomes));
+    }
+
+    if rows.is_empty() {
+        println!("No inputs found in \"{}\".", dir.display());
+        return Ok(());
+    }
+
+    print!("{}", render_matrix(&rows));
+
+    let mut failed = 0;
+    for (day, outcomes) in &rows {
+        for (name, outcome) in outcomes {
+            match outcome {
+                Outcome::Failed(failures) => {
+                    failed += 1;
+                    for failure in failures {
+                        println!("Day {day} {name}: {failure}");
+                    }
+                }
+                Outcome::Error(e) => {
+                    failed += 1;
+                    println!("Day {day} {name}: {e}");
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let total: usize = rows.iter().map(|(_, outcomes)| outcomes.len()).sum();
+    let unchecked = rows
+        .iter()
+        .flat_map(|(_, outcomes)| outcomes)
+        .filter(|(_, outcome)| *outcome == Outcome::Unchecked)
+        .count();
+    println!(
+        "\n{} passed, {failed} failed, {unchecked} without answers.",
+        total - failed - unchecked
+    );
+
+    if failed > 0 {
+        Err(Error::CorpusFailed(failed))
+    } else {
+        Ok(())
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::fs;
+
+    use super::{compare, find_inputs, parse_results, render_matrix, Outcome};
+    use crate::day;
+    use crate::template::answers::Answers;
+
+    fn answers(part_one: Option<&str>, part_two: Option<&str>) -> Answers {
+        Answers {
+            part_one: part_one.map(Into::into),
+            part_two: part_two.map(Into::into),
+        }
+    }
+
+    #[test]
+    fn finds_inputs_for_a_day() {
+        let dir = std::env::temp_dir().join(format!("aoc-corpus-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        for name in [
+            "01-bob.txt",
+            "01-alice.txt",
+            "01.txt",
+            "01-alice.answers",
+            "011.txt",
+            "02-alice.txt",
+        ] {
+            fs::write(dir.join(name), "1\n").unwrap();
+        }
+
+        let inputs = find_inputs(&dir, day!(1)).unwrap();
+        let names: Vec<_> = inputs.iter().map(|input| input.name.as_str()).collect();
+        assert_eq!(names, ["alice", "bob", "default"]);
+        assert_eq!(inputs[0].answers_path(), dir.join("01-alice.answers"));
+
+        assert!(find_inputs(&dir, day!(11)).unwrap().is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn parses_json_results() {
+        let output = [
+            r#"{"part":1,"result":"42","nanos":10,"samples":1}"#.to_string(),
+            "not json".to_string(),
+            r#"{"reason":"build-finished"}"#.to_string(),
+            r#"{"part":2,"result":null,"nanos":10,"samples":1}"#.to_string(),
+        ];
+        assert_eq!(
+            parse_results(&output),
+            [(1, Some("42".to_string())), (2, None)]
+        );
+    }
+
+    #[test]
+    fn compares_results_to_answers() {
+        let results = [(1, Some("42".to_string())), (2, None)];
+
+        assert_eq!(
+            compare(&results, &answers(Some("42"), None)),
+            Outcome::Passed
+        );
+        assert_eq!(compare(&results, &answers(None, None)), Outcome::Unchecked);
+        assert_eq!(
+            compare(&results, &answers(Some("41"), Some("7"))),
+            Outcome::Failed(vec![
+                "part 1: expected 41, got 42".into(),
+                "part 2: expected 7, got ✖".into(),
+            ])
+        );
+    }
+
+    #[test]
+    fn renders_a_matrix() {
+        let rows = [
+            (
+                day!(1),
+                vec![
+                    ("alice".to_string(), Outcome::Passed),
+                    ("bob".to_string(), Outcome::Failed(vec![])),
+                ],
+            ),
+            (day!(2), vec![("bob".to_string(), Outcome::Unchecked)]),
+        ];
+        let matrix = render_matrix(&rows);
+        let lines: Vec<_> = matrix.lines().skip(1).collect();
+        assert_eq!(lines, ["01  | ✔     | ✖  ", "02  | -     | ?  "]);
+    }
+}
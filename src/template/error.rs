@@ -0,0 +1,203 @@
+/// Errors returned by the template commands. Rendering them and choosing an exit code is left to the caller.
+use std::{
+    fmt::Display,
+    io,
+    path::PathBuf,
+    process::{Command, ExitStatus},
+    time::Duration,
+};
+
+use crate::template::aoc_cli::AocCommandError;
+use crate::template::input::InputProblem;
+use crate::template::Day;
+
+#[derive(Debug)]
+pub enum Error {
+    AocCli(AocCommandError),
+    /// A scaffold template could not be found or read.
+    Template(String),
+    /// A puzzle input is missing or does not look like an input.
+    InvalidInput {
+        path: PathBuf,
+        problem: InputProblem,
+    },
+    /// A file could not be read or written.
+    File {
+        path: PathBuf,
+        source: io::Error,
+    },
+    /// A child process like `cargo` could not be started.
+    Command {
+        command: String,
+        source: io::Error,
+    },
+    /// A child process exited with a non-zero status.
+    CommandFailed {
+        command: String,
+        status: ExitStatus,
+    },
+    /// A solution ran for longer than the configured timeout and was killed.
+    Timeout(Duration),
+    /// The benchmarks table in the readme could not be updated.
+    Readme(String),
+    /// Some inputs of a corpus did not match their answers, or their solution could not be run.
+    CorpusFailed(usize),
+    /// There is no input generator for a day in `src/gen.rs`.
+    NoGenerator(Day),
+    /// `today` was run outside of the 1st to the 25th of december.
+    NotAdventDay,
+    /// A solution and its reference disagreed on a random input, stored at `input`.
+    StressFailed {
+        seed: u64,
+        input: PathBuf,
+    },
+    Io(io::Error),
+}
+
+impl Error {
+    /// The exit code of the CLI for this error. Failed child processes pass their own exit code through.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::CommandFailed { status, .. } => status.code().filter(|&c| c != 0).unwrap_or(1),
+            // same as coreutils' `timeout`.
+            Error::Timeout(_) => 124,
+            _ => 1,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::AocCli(AocCommandError::CommandNotFound) => write!(
+                f,
+                "command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it."
+            ),
+            Error::AocCli(e) => write!(f, "failed to call aoc-cli: {e}"),
+            Error::Template(e) => write!(f, "failed to read template: {e}"),
+            Error::InvalidInput { path, problem } => {
+                write!(f, "puzzle input \"{}\" can not be used: {problem}", path.display())
+            }
+            Error::File { path, source } => write!(f, "\"{}\": {source}", path.display()),
+            Error::Command { command, source } => write!(f, "failed to run `{command}`: {source}"),
+            Error::CommandFailed { command, status } => write!(f, "`{command}` exited with {status}"),
+            Error::Timeout(timeout) => write!(f, "solution timed out after {timeout:?}"),
+            Error::Readme(e) => write!(f, "failed to store updated benchmarks: {e}"),
+            Error::CorpusFailed(1) => f.write_str("1 input of the corpus failed"),
+            Error::CorpusFailed(n) => write!(f, "{n} inputs of the corpus failed"),
+            Error::NoGenerator(day) => write!(f, "there is no input generator for day {day}"),
+            Error::NotAdventDay => f.write_str(
+                "`today` can only be run between the 1st and the 25th of december, use `scaffold` with a specific day",
+            ),
+            Error::StressFailed { seed, input } => write!(
+                f,
+                "the solutions disagree on the input generated with seed {seed}, stored in \"{}\"",
+                input.display()
+            ),
+            Error::Io(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::File { source, .. } | Error::Command { source, .. } => Some(source),
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<AocCommandError> for Error {
+    fn from(e: AocCommandError) -> Self {
+        Error::AocCli(e)
+    }
+}
+
+/// A short description of a command for error messages, e.g. `cargo build`.
+pub(crate) fn describe_command(cmd: &Command) -> String {
+    let program = cmd.get_program().to_string_lossy();
+    match cmd.get_args().next() {
+        Some(arg) => format!("{program} {}", arg.to_string_lossy()),
+        None => program.into_owned(),
+    }
+}
+
+/// Run a command to completion, failing if it can not be started or exits with a non-zero status.
+pub(crate) fn run_command(cmd: &mut Command) -> Result<(), Error> {
+    let status = cmd.status().map_err(|source| Error::Command {
+        command: describe_command(cmd),
+        source,
+    })?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(Error::CommandFailed {
+            command: describe_command(cmd),
+            status,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{process::Command, time::Duration};
+
+    use super::{run_command, Error};
+    use crate::template::aoc_cli::AocCommandError;
+    use crate::template::input::InputProblem;
+
+    #[test]
+    fn fails_for_missing_programs() {
+        let err = run_command(&mut Command::new("aoc-template-missing-program")).unwrap_err();
+        assert!(matches!(err, Error::Command { .. }));
+        assert_eq!(err.exit_code(), 1);
+        assert!(err
+            .to_string()
+            .starts_with("failed to run `aoc-template-missing-program`"));
+    }
+
+    #[test]
+    fn passes_through_exit_codes() {
+        let err = run_command(Command::new("cargo").arg("--not-a-cargo-flag")).unwrap_err();
+        let Error::CommandFailed { command, status } = &err else {
+            panic!("expected a failed command, got {err:?}");
+        };
+        assert_eq!(command, "cargo --not-a-cargo-flag");
+        assert_eq!(Some(err.exit_code()), status.code());
+    }
+
+    #[test]
+    fn renders_errors() {
+        assert_eq!(Error::Timeout(Duration::from_secs(2)).exit_code(), 124);
+        assert!(Error::AocCli(AocCommandError::CommandNotFound)
+            .to_string()
+            .contains("cargo install aoc-cli"));
+    }
+
+    #[test]
+    fn renders_invalid_inputs() {
+        let render = |problem| {
+            Error::InvalidInput {
+                path: "data/inputs/01.txt".into(),
+                problem,
+            }
+            .to_string()
+        };
+        assert_eq!(
+            render(InputProblem::Empty),
+            "puzzle input \"data/inputs/01.txt\" can not be used: the file is empty"
+        );
+        assert!(render(InputProblem::Html).ends_with("an HTML page instead of an input"));
+    }
+}
@@ -0,0 +1,240 @@
+/// Integrity checks for puzzle inputs, and a local cache of downloaded inputs keyed by year and day.
+///
+/// The cache lives outside the project (by default in the user's cache directory), so inputs survive re-scaffolding
+/// and can be restored without a session cookie or network access.
+use std::{
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::template::{Config, Day};
+
+/// Ways in which an input file can be unusable.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InputProblem {
+    Missing,
+    Unreadable(String),
+    /// The file is empty or only contains whitespace, e.g. the stub created by `scaffold`.
+    Empty,
+    /// The file contains one of the messages the puzzle server sends instead of an input.
+    Placeholder,
+    /// The file contains an HTML page, e.g. an error page that was saved as the input.
+    Html,
+}
+
+impl Display for InputProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputProblem::Missing => f.write_str("the file does not exist"),
+            InputProblem::Unreadable(e) => write!(f, "the file could not be read: {e}"),
+            InputProblem::Empty => f.write_str("the file is empty"),
+            InputProblem::Placeholder => f.write_str(
+                "the file contains a message from the puzzle server instead of an input",
+            ),
+            InputProblem::Html => f.write_str("the file contains an HTML page instead of an input"),
+        }
+    }
+}
+
+/// Messages the puzzle server sends instead of an input, e.g. when the session cookie is missing.
+const PLACEHOLDERS: [&str; 3] = [
+    "Please log in to get your puzzle input",
+    "Please don't repeatedly request this endpoint before it unlocks",
+    "404 Not Found",
+];
+
+/// Check that an input looks like a real puzzle input.
+pub fn check_input(contents: &str) -> Result<(), InputProblem> {
+    let trimmed = contents.trim_start_matches('\u{feff}').trim();
+
+    if trimmed.is_empty() {
+        return Err(InputProblem::Empty);
+    }
+
+    // placeholders are short, single-line messages.
+    if trimmed.len() < 512 && PLACEHOLDERS.iter().any(|p| trimmed.contains(p)) {
+        return Err(InputProblem::Placeholder);
+    }
+
+    let is_html = ["<!doctype html", "<html"].iter().any(|tag| {
+        trimmed
+            .as_bytes()
+            .get(..tag.len())
+            .is_some_and(|start| start.eq_ignore_ascii_case(tag.as_bytes()))
+    });
+    if is_html {
+        return Err(InputProblem::Html);
+    }
+
+    Ok(())
+}
+
+/// Normalise an input, so parsers only have to handle a single format: strips a byte order mark, converts CRLF line
+/// endings to LF, and makes sure the input ends with exactly one newline. Empty inputs stay empty.
+pub fn normalize(contents: &str) -> String {
+    let contents = contents.strip_prefix('\u{feff}').unwrap_or(contents);
+    let trimmed = contents.trim_end_matches(['\r', '\n']);
+    if trimmed.is_empty() {
+        return String::new();
+    }
+
+    let mut normalized = trimmed.replace("\r\n", "\n");
+    normalized.push('\n');
+    normalized
+}
+
+/// Read an input file, check that it looks like a real puzzle input, and normalise it.
+pub fn read_checked(path: impl AsRef<Path>) -> Result<String, InputProblem> {
+    let contents = fs::read_to_string(path).map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => InputProblem::Missing,
+        _ => InputProblem::Unreadable(e.to_string()),
+    })?;
+    check_input(&contents)?;
+    Ok(normalize(&contents))
+}
+
+/// A human-readable explanation of why an input can not be used, with a hint on how to fix it.
+pub fn describe_problem(path: &Path, day: Day, problem: &InputProblem) -> String {
+    format!(
+        "puzzle input \"{}\" can not be used: {problem}. Run `cargo download {day}` to download it.",
+        path.display()
+    )
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A cache of puzzle inputs, stored as `<dir>/<year>/<day>.txt`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InputCache {
+    dir: PathBuf,
+}
+
+impl InputCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The cache configured in `paths.cache`.
+    pub fn from_config() -> Self {
+        Self::new(&Config::get().paths.cache)
+    }
+
+    pub fn path(&self, year: u16, day: Day) -> PathBuf {
+        self.dir.join(year.to_string()).join(format!("{day}.txt"))
+    }
+
+    /// Returns the cached input for a day, if there is a valid one.
+    pub fn get(&self, year: u16, day: Day) -> Option<String> {
+        read_checked(self.path(year, day)).ok()
+    }
+
+    /// Store a valid input in the cache.
+    pub fn put(&self, year: u16, day: Day, contents: &str) -> io::Result<()> {
+        if let Err(problem) = check_input(contents) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                problem.to_string(),
+            ));
+        }
+        let path = self.path(year, day);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, contents)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::fs;
+
+    use super::{check_input, normalize, InputCache, InputProblem};
+    use crate::day;
+    use crate::template::Config;
+
+    #[test]
+    fn accepts_inputs() {
+        assert_eq!(check_input("1 2 3\n4 5 6\n"), Ok(()));
+        assert_eq!(check_input("<<>>\n"), Ok(()));
+    }
+
+    #[test]
+    fn rejects_empty_inputs() {
+        assert_eq!(check_input(""), Err(InputProblem::Empty));
+        assert_eq!(check_input("\u{feff}\n  \n"), Err(InputProblem::Empty));
+    }
+
+    #[test]
+    fn rejects_placeholders() {
+        assert_eq!(
+            check_input("Puzzle inputs differ by user.  Please log in to get your puzzle input.\n"),
+            Err(InputProblem::Placeholder)
+        );
+        assert_eq!(
+            check_input("404 Not Found\n"),
+            Err(InputProblem::Placeholder)
+        );
+    }
+
+    #[test]
+    fn rejects_html_pages() {
+        assert_eq!(
+            check_input("<!DOCTYPE html>\n<html lang=\"en-us\">\n</html>"),
+            Err(InputProblem::Html)
+        );
+        assert_eq!(
+            check_input("  <html><body>500</body></html>"),
+            Err(InputProblem::Html)
+        );
+    }
+
+    #[test]
+    fn normalizes_inputs() {
+        assert_eq!(normalize("1 2\r\n3 4\r\n"), "1 2\n3 4\n");
+        assert_eq!(normalize("\u{feff}1 2\n3 4"), "1 2\n3 4\n");
+        assert_eq!(normalize("1 2\n\n3 4\n\n\n"), "1 2\n\n3 4\n");
+        assert_eq!(normalize("\r\n"), "");
+        assert_eq!(normalize(""), "");
+    }
+
+    #[test]
+    fn normalizes_examples_regardless_of_line_endings() {
+        let examples = fs::read_dir(&Config::get().paths.examples).unwrap();
+        for entry in examples {
+            let path = entry.unwrap().path();
+            if path.extension().is_none_or(|ext| ext != "txt") {
+                continue;
+            }
+            let contents = fs::read_to_string(&path).unwrap();
+            let expected = normalize(&contents);
+
+            let windows = format!("\u{feff}{}", contents.replace('\n', "\r\n"));
+            assert_eq!(normalize(&windows), expected, "{}", path.display());
+            assert_eq!(
+                normalize(contents.trim_end()),
+                expected,
+                "{}",
+                path.display()
+            );
+            assert_eq!(normalize(&expected), expected, "{}", path.display());
+        }
+    }
+
+    #[test]
+    fn caches_valid_inputs() {
+        let dir = std::env::temp_dir().join(format!("aoc-input-cache-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let cache = InputCache::new(&dir);
+
+        assert_eq!(cache.get(2024, day!(3)), None);
+        cache.put(2024, day!(3), "mul(2,4)\n").unwrap();
+        assert_eq!(cache.get(2024, day!(3)).as_deref(), Some("mul(2,4)\n"));
+        assert_eq!(cache.get(2023, day!(3)), None);
+
+        assert!(cache.put(2024, day!(4), "").is_err());
+        assert_eq!(cache.get(2024, day!(4)), None);
+    }
+}
@@ -1,11 +1,21 @@
 use std::{env, fs};
 
+pub mod answers;
 pub mod aoc_cli;
+pub mod args;
 pub mod commands;
+pub mod config;
+pub mod context;
+pub mod error;
+pub mod input;
 pub mod runner;
 
+pub use config::Config;
+pub use context::{Context, Params};
 pub use day::*;
+pub use error::Error;
 
+mod corpus;
 mod day;
 mod readme_benchmarks;
 mod run_multi;
@@ -15,30 +25,62 @@ pub const ANSI_ITALIC: &str = "\x1b[3m";
 pub const ANSI_BOLD: &str = "\x1b[1m";
 pub const ANSI_RESET: &str = "\x1b[0m";
 
-/// Helper function that reads a text file to a string.
+/// Helper function that reads a text file to a string, normalised with [`input::normalize`].
+///
+/// Panics with a diagnostic if the file can not be read, or if a puzzle input does not look like one.
 #[must_use]
 pub fn read_file(folder: &str, day: Day) -> String {
-    let cwd = env::current_dir().unwrap();
-    let filepath = cwd.join("data").join(folder).join(format!("{day}.txt"));
-    let f = fs::read_to_string(filepath);
-    f.expect("could not open input file")
+    read_data_file(folder, day, &format!("{day}.txt"))
 }
 
 /// Helper function that reads a text file to string, appending a part suffix. E.g. like `01-2.txt`.
 #[must_use]
 pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
+    read_data_file(folder, day, &format!("{day}-{part}.txt"))
+}
+
+fn read_data_file(folder: &str, day: Day, name: &str) -> String {
     let cwd = env::current_dir().unwrap();
-    let filepath = cwd
-        .join("data")
-        .join(folder)
-        .join(format!("{day}-{part}.txt"));
-    let f = fs::read_to_string(filepath);
-    f.expect("could not open input file")
+    let filepath = cwd.join(Config::get().data_folder(folder)).join(name);
+
+    // examples are allowed to be empty, as `scaffold` creates them that way.
+    if folder == "inputs" {
+        return input::read_checked(&filepath).unwrap_or_else(|problem| {
+            panic!("{}", input::describe_problem(&filepath, day, &problem))
+        });
+    }
+
+    let contents = fs::read_to_string(&filepath)
+        .unwrap_or_else(|e| panic!("could not read \"{}\": {e}", filepath.display()));
+    input::normalize(&contents)
+}
+
+/// Helper function that reads the context for a file from its params file, e.g. `examples/14.params`.
+/// Falls back to the default context if there is no params file.
+#[must_use]
+pub fn read_context<C: Context>(folder: &str, day: Day) -> C {
+    let filepath = Config::get()
+        .data_folder(folder)
+        .join(format!("{day}.params"));
+    context::read_context_from_file(filepath).expect("could not read params file")
+}
+
+/// Helper function that reads the context for a file with a part suffix. E.g. like `01-2.params`.
+#[must_use]
+pub fn read_context_part<C: Context>(folder: &str, day: Day, part: u8) -> C {
+    let filepath = Config::get()
+        .data_folder(folder)
+        .join(format!("{day}-{part}.params"));
+    context::read_context_from_file(filepath).expect("could not read params file")
 }
 
 /// Creates the constant `DAY` and sets up the input and runner for each part.
 ///
 /// The optional, second parameter (1 or 2) allows you to only run a single part of the solution.
+///
+/// Solutions whose parameters differ between the examples and the real input can take a [`Context`] with
+/// `solution!(DD, ctx: Type)`. Each part then has the signature `fn part_one(input: &str, ctx: &Type)`, and the context
+/// is read from the params file of the selected input.
 #[macro_export]
 macro_rules! solution {
     ($day:expr) => {
@@ -50,19 +92,52 @@ macro_rules! solution {
     ($day:expr, 2) => {
         $crate::solution!(@impl $day, [part_two, 2]);
     };
+    ($day:expr, ctx: $ctx:ty) => {
+        $crate::solution!(@impl $day, ctx: $ctx, [part_one, 1] [part_two, 2]);
+    };
+    ($day:expr, 1, ctx: $ctx:ty) => {
+        $crate::solution!(@impl $day, ctx: $ctx, [part_one, 1]);
+    };
+    ($day:expr, 2, ctx: $ctx:ty) => {
+        $crate::solution!(@impl $day, ctx: $ctx, [part_two, 2]);
+    };
 
     (@impl $day:expr, $( [$func:expr, $part:expr] )*) => {
+        $crate::solution!(@header $day);
+
+        fn main() {
+            use $crate::template::runner::*;
+            let args = parse_args();
+            let input = read_input(&args, DAY);
+            $(
+                if args.runs_part($part) {
+                    run_part($func, &input, DAY, $part, &args);
+                }
+            )*
+        }
+    };
+    (@impl $day:expr, ctx: $ctx:ty, $( [$func:expr, $part:expr] )*) => {
+        $crate::solution!(@header $day);
+
+        fn main() {
+            use $crate::template::runner::*;
+            let args = parse_args();
+            let input = read_input(&args, DAY);
+            let ctx: $ctx = read_context(&args, DAY);
+            $(
+                if args.runs_part($part) {
+                    run_part(|input: &str| $func(input, &ctx), input.as_str(), DAY, $part, &args);
+                }
+            )*
+        }
+    };
+
+    (@header $day:expr) => {
         /// The current day.
         const DAY: $crate::template::Day = $crate::day!($day);
 
         #[cfg(feature = "dhat-heap")]
         #[global_allocator]
         static ALLOC: dhat::Alloc = dhat::Alloc;
-
-        fn main() {
-            use $crate::template::runner::*;
-            let input = $crate::template::read_file("inputs", DAY);
-            $( run_part($func, &input, DAY, $part); )*
-        }
     };
 }
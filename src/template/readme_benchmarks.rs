@@ -3,7 +3,7 @@
 use std::{fs, io};
 
 use crate::template::timings::Timings;
-use crate::template::Day;
+use crate::template::{Config, Day};
 
 static MARKER: &str = "<!--- benchmarking table --->";
 
@@ -20,6 +20,18 @@ impl From<std::io::Error> for Error {
     }
 }
 
+impl From<Error> for crate::template::Error {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::Parser(e) => crate::template::Error::Readme(e),
+            Error::IO(source) => crate::template::Error::File {
+                path: Config::get().paths.readme.clone(),
+                source,
+            },
+        }
+    }
+}
+
 pub struct TablePosition {
     pos_start: usize,
     pos_end: usize,
@@ -27,7 +39,7 @@ pub struct TablePosition {
 
 #[must_use]
 pub fn get_path_for_bin(day: Day) -> String {
-    format!("./src/bin/{day}.rs")
+    format!("./{}", Config::get().bin_path(day).display())
 }
 
 fn locate_table(readme: &str) -> Result<TablePosition, Error> {
@@ -89,7 +101,7 @@ fn update_content(s: &mut String, timings: Timings, total_millis: f64) -> Result
 }
 
 pub fn update(timings: Timings) -> Result<(), Error> {
-    let path = "README.md";
+    let path = &Config::get().paths.readme;
     let mut readme = String::from_utf8_lossy(&fs::read(path)?).to_string();
     let total_millis = timings.total_millis();
     update_content(&mut readme, timings, total_millis)?;
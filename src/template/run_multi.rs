@@ -1,38 +1,56 @@
-use std::{collections::HashSet, io};
+use std::{collections::HashSet, path::Path};
 
-use crate::template::{Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+use crate::template::{Config, Day, Error, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
 
 use super::{
     all_days,
     timings::{Timing, Timings},
 };
 
-pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -> Option<Timings> {
+/// Run the solutions for `days_to_run`. If `input_dir` is set, each day is run against `<input_dir>/DD.txt` instead of
+/// its puzzle input, and days without an input in that directory are skipped.
+pub fn run_multi(
+    days_to_run: &HashSet<Day>,
+    is_release: bool,
+    is_timed: bool,
+    input_dir: Option<&Path>,
+) -> Result<Option<Timings>, Error> {
     let mut timings: Vec<Timing> = Vec::with_capacity(days_to_run.len());
 
     let mut need_space = false;
 
     // NOTE: use non-duplicate, sorted day values.
-    all_days()
-        .filter(|day| days_to_run.contains(day))
-        .for_each(|day| {
-            if need_space {
-                println!();
-            }
-            need_space = true;
+    for day in all_days().filter(|day| days_to_run.contains(day)) {
+        if need_space {
+            println!();
+        }
+        need_space = true;
 
-            println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
-            println!("------");
+        println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
+        println!("------");
 
-            let output = child_commands::run_solution(day, is_timed, is_release).unwrap();
+        let input = input_dir.map(|dir| dir.join(format!("{day}.txt")));
+        if let Some(input) = input.as_deref().filter(|input| !input.exists()) {
+            println!("No input at \"{}\".", input.display());
+            continue;
+        }
 
-            if output.is_empty() {
-                println!("Not solved.");
-            } else {
-                let val = child_commands::parse_exec_time(&output, day);
-                timings.push(val);
+        let output = match child_commands::run_solution(day, is_timed, is_release, input.as_deref())
+        {
+            Err(Error::Timeout(_)) => {
+                println!("Timed out.");
+                continue;
             }
-        });
+            res => res?,
+        };
+
+        if output.is_empty() {
+            println!("Not solved.");
+        } else {
+            let val = child_commands::parse_exec_time(&output, day);
+            timings.push(val);
+        }
+    }
 
     if is_timed {
         let timings = Timings { data: timings };
@@ -40,44 +58,62 @@ pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -
         println!(
             "\n{ANSI_BOLD}Total (Run):{ANSI_RESET} {ANSI_ITALIC}{total_millis:.2}ms{ANSI_RESET}"
         );
-        Some(timings)
+        Ok(Some(timings))
     } else {
-        None
-    }
-}
-
-#[allow(dead_code)]
-#[derive(Debug)]
-pub enum Error {
-    BrokenPipe,
-    IO(io::Error),
-}
-
-impl From<std::io::Error> for Error {
-    fn from(e: std::io::Error) -> Self {
-        Error::IO(e)
+        Ok(None)
     }
 }
 
 #[must_use]
 pub fn get_path_for_bin(day: Day) -> String {
-    format!("./src/bin/{day}.rs")
+    Config::get().bin_path(day).display().to_string()
 }
 
 /// All solutions live in isolated binaries.
 /// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
 pub mod child_commands {
-    use super::{get_path_for_bin, Error};
-    use crate::template::Day;
+    use super::get_path_for_bin;
+    use crate::template::error::{describe_command, run_command};
+    use crate::template::{Config, Day, Error};
     use std::{
-        io::{BufRead, BufReader},
+        io::{self, BufRead, BufReader},
         path::Path,
-        process::{Command, Stdio},
+        process::{Child, Command, ExitStatus, Stdio},
         thread,
+        time::{Duration, Instant},
     };
 
-    /// Run the solution bin for a given day
-    pub fn run_solution(day: Day, is_timed: bool, is_release: bool) -> Result<Vec<String>, Error> {
+    /// Run the solution bin for a given day, against `input` instead of the puzzle input if set.
+    pub fn run_solution(
+        day: Day,
+        is_timed: bool,
+        is_release: bool,
+        input: Option<&Path>,
+    ) -> Result<Vec<String>, Error> {
+        let input = input.map(|path| path.to_string_lossy());
+        let mut bin_args = vec![];
+
+        if is_timed {
+            // mirror `--time` flag to child invocations.
+            bin_args.push("--time");
+        }
+
+        if let Some(input) = &input {
+            bin_args.push("--input");
+            bin_args.push(input);
+        }
+
+        run_bin(day, is_release, &bin_args, true)
+    }
+
+    /// Run the solution bin for a given day with `bin_args` and return the lines it printed to stdout.
+    /// The lines are forwarded to stdout as well if `echo` is set.
+    pub fn run_bin(
+        day: Day,
+        is_release: bool,
+        bin_args: &[&str],
+        echo: bool,
+    ) -> Result<Vec<String>, Error> {
         // skip command invocation for days that have not been scaffolded yet.
         if !Path::new(&get_path_for_bin(day)).exists() {
             return Ok(vec![]);
@@ -90,42 +126,86 @@ pub mod child_commands {
             args.push("--release");
         }
 
-        if is_timed {
-            // mirror `--time` flag to child invocations.
+        let timeout = Config::get().run.timeout;
+
+        if timeout.is_some() {
+            // build separately so compilation does not count towards the timeout.
+            run_command(Command::new("cargo").args(["build"]).args(&args[1..]))?;
+        }
+
+        if !bin_args.is_empty() {
             args.push("--");
-            args.push("--time");
+            args.extend_from_slice(bin_args);
         }
 
         // spawn child command with piped stdout/stderr.
         // forward output to stdout/stderr while grabbing stdout lines.
 
-        let mut cmd = Command::new("cargo")
+        let mut command = Command::new("cargo");
+        command
             .args(&args)
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()?;
+            .stderr(Stdio::piped());
 
-        let stdout = BufReader::new(cmd.stdout.take().ok_or(super::Error::BrokenPipe)?);
-        let stderr = BufReader::new(cmd.stderr.take().ok_or(super::Error::BrokenPipe)?);
+        let mut cmd = command.spawn().map_err(|source| Error::Command {
+            command: describe_command(&command),
+            source,
+        })?;
 
-        let mut output = vec![];
+        let stdout = BufReader::new(cmd.stdout.take().expect("stdout is piped"));
+        let stderr = BufReader::new(cmd.stderr.take().expect("stderr is piped"));
 
-        let thread = thread::spawn(move || {
+        let stderr_thread = thread::spawn(move || {
             stderr.lines().for_each(|line| {
                 eprintln!("{}", line.unwrap());
             });
         });
 
-        for line in stdout.lines() {
-            let line = line.unwrap();
-            println!("{line}");
-            output.push(line);
+        let stdout_thread = thread::spawn(move || {
+            let mut output = vec![];
+            for line in stdout.lines() {
+                let line = line.unwrap();
+                if echo {
+                    println!("{line}");
+                }
+                output.push(line);
+            }
+            output
+        });
+
+        let status = wait_with_timeout(&mut cmd, timeout)?;
+
+        let output = stdout_thread.join().unwrap();
+        stderr_thread.join().unwrap();
+
+        match status {
+            Some(_) => Ok(output),
+            None => Err(Error::Timeout(timeout.unwrap_or_default())),
         }
+    }
 
-        thread.join().unwrap();
-        cmd.wait()?;
+    /// Wait for a child process to exit, killing it once `timeout` has passed.
+    /// Returns `None` if the child was killed.
+    pub fn wait_with_timeout(
+        cmd: &mut Child,
+        timeout: Option<Duration>,
+    ) -> io::Result<Option<ExitStatus>> {
+        let Some(timeout) = timeout else {
+            return cmd.wait().map(Some);
+        };
 
-        Ok(output)
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(status) = cmd.try_wait()? {
+                return Ok(Some(status));
+            }
+            if Instant::now() >= deadline {
+                cmd.kill()?;
+                cmd.wait()?;
+                return Ok(None);
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
     }
 
     pub fn parse_exec_time(output: &[String], day: Day) -> super::Timing {
@@ -1,33 +1,114 @@
 /// Encapsulates code that interacts with solution functions.
+use std::collections::HashMap;
 use std::fmt::Display;
 use std::hint::black_box;
 use std::io::{stdout, Write};
 use std::process::Output;
 use std::time::{Duration, Instant};
-use std::{cmp, env, process};
+use std::{cmp, process};
 
+use tinyjson::JsonValue;
+
+use crate::template::answers::read_example_answers;
+use crate::template::args::{InputSource, OutputFormat, SolutionArgs, USAGE};
+use crate::template::context::{read_context_from_file, Context};
+use crate::template::input::{describe_problem, normalize, read_checked};
 use crate::template::ANSI_BOLD;
-use crate::template::{aoc_cli, Day, ANSI_ITALIC, ANSI_RESET};
+use crate::template::{aoc_cli, Config, Day, ANSI_ITALIC, ANSI_RESET};
+use crate::viz;
+
+/// Parse the arguments passed to a solution binary, exiting with a usage message if they are invalid.
+/// Turns on [`viz`] for `--viz`.
+pub fn parse_args() -> SolutionArgs {
+    let args = SolutionArgs::from_env().unwrap_or_else(|e| {
+        eprintln!("Error: {e}\n\n{USAGE}");
+        process::exit(1);
+    });
+    if args.viz {
+        viz::enable(viz::Options {
+            delay: args.viz_delay,
+        });
+    }
+    args
+}
+
+/// Read the input selected by the arguments, exiting if it can not be read.
+pub fn read_input(args: &SolutionArgs, day: Day) -> String {
+    if args.input == InputSource::Puzzle {
+        let path = Config::get().input_path(day);
+        return read_checked(&path).unwrap_or_else(|problem| {
+            eprintln!("Error: {}", describe_problem(&path, day, &problem));
+            process::exit(1);
+        });
+    }
 
-pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
+    let input = args.read_input(day).unwrap_or_else(|e| {
+        eprintln!("Error: could not read {}: {e}", args.input);
+        process::exit(1);
+    });
+    normalize(&input)
+}
+
+/// Read the context for the selected input from its params file, exiting if it is invalid.
+pub fn read_context<C: Context>(args: &SolutionArgs, day: Day) -> C {
+    let Some(path) = args.params_path(day) else {
+        return C::default();
+    };
+    read_context_from_file(&path).unwrap_or_else(|e| {
+        eprintln!(
+            "Error: could not read params file \"{}\": {e}",
+            path.display()
+        );
+        process::exit(1);
+    })
+}
+
+pub fn run_part<I: Clone, T: Display>(
+    func: impl Fn(I) -> Option<T>,
+    input: I,
+    day: Day,
+    part: u8,
+    args: &SolutionArgs,
+) {
     let part_str = format!("Part {part}");
+    let is_human = args.format == OutputFormat::Human;
+
+    let (result, duration, samples) = run_timed(func, input, args, |result| {
+        if is_human {
+            print_result(result, &part_str, "");
+        }
+    });
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+    let expected = match args.input {
+        InputSource::Example(k) => read_example_answers(day, k).get(part).map(String::from),
+        _ => None,
+    };
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+    match args.format {
+        OutputFormat::Human => {
+            print_result(&result, &part_str, &format_duration(&duration, samples));
+            if let Some(expected) = &expected {
+                print_expected(&result, expected);
+            }
+        }
+        OutputFormat::Json => print_json(&result, expected, part, &duration, samples),
+    }
 
     if let Some(result) = result {
-        submit_result(result, day, part);
+        if let Some(Err(e)) = submit_result(result, day, part, args) {
+            eprintln!("Failed to submit result: {e}");
+        }
     }
 }
 
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
-///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
+///  2. in release, the function is benched (approx. `bench.target_time` of execution time or `bench.min_iterations`
+///     samples, whatever take longer.)
 fn run_timed<I: Clone, T>(
     func: impl Fn(I) -> T,
     input: I,
+    args: &SolutionArgs,
     hook: impl Fn(&T),
 ) -> (T, Duration, u128) {
     let timer = Instant::now();
@@ -43,8 +124,8 @@ fn run_timed<I: Clone, T>(
 
     hook(&result);
 
-    let run = if std::env::args().any(|x| x == "--time") {
-        bench(func, input, &base_time)
+    let run = if args.is_timed() {
+        bench(func, input, &base_time, args)
     } else {
         (base_time, 1)
     };
@@ -52,14 +133,22 @@ fn run_timed<I: Clone, T>(
     (result, run.0, run.1)
 }
 
-fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (Duration, u128) {
-    let mut stdout = stdout();
-
-    print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
-    let _ = stdout.flush();
+fn bench<I: Clone, T>(
+    func: impl Fn(I) -> T,
+    input: I,
+    base_time: &Duration,
+    args: &SolutionArgs,
+) -> (Duration, u128) {
+    if args.format == OutputFormat::Human {
+        print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
+        let _ = stdout().flush();
+    }
 
-    let bench_iterations =
-        (Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10)).clamp(10, 10000);
+    let bench_iterations = args.iterations.unwrap_or_else(|| {
+        let config = &Config::get().bench;
+        (config.target_time.as_nanos() / cmp::max(base_time.as_nanos(), 10))
+            .clamp(config.min_iterations, config.max_iterations)
+    });
 
     let mut timers: Vec<Duration> = vec![];
 
@@ -129,39 +218,70 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
     }
 }
 
-/// Parse the arguments passed to `solve` and try to submit one part of the solution if:
-///  1. we are in `--release` mode.
-///  2. aoc-cli is installed.
-fn submit_result<T: Display>(
-    result: T,
-    day: Day,
-    part: u8,
-) -> Option<Result<Output, aoc_cli::AocCommandError>> {
-    let args: Vec<String> = env::args().collect();
+/// Print the expected answer of an example below its result.
+fn print_expected<T: Display>(result: &Option<T>, expected: &str) {
+    let is_correct = result
+        .as_ref()
+        .is_some_and(|result| result.to_string().trim() == expected.trim());
 
-    if !args.contains(&"--submit".into()) {
-        return None;
+    if is_correct {
+        println!("        {ANSI_ITALIC}expected {expected} ✔{ANSI_RESET}");
+    } else {
+        println!("        {ANSI_ITALIC}expected {expected} ✖{ANSI_RESET}");
     }
+}
 
-    if args.len() < 3 {
-        eprintln!("Unexpected command-line input. Format: cargo solve 1 --submit 1");
-        process::exit(1);
+/// Print a result as a single line of JSON, e.g. `{"part":1,"result":"42","nanos":166.0,"samples":1}`.
+/// When running an example with a known answer, the answer is included as `expected`.
+fn print_json<T: Display>(
+    result: &Option<T>,
+    expected: Option<String>,
+    part: u8,
+    duration: &Duration,
+    samples: u128,
+) {
+    let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+    if let Some(expected) = expected {
+        map.insert("expected".into(), JsonValue::String(expected));
     }
 
-    let part_index = args.iter().position(|x| x == "--submit").unwrap() + 1;
+    map.insert("part".into(), JsonValue::Number(part.into()));
+    map.insert(
+        "result".into(),
+        match result {
+            Some(result) => JsonValue::String(result.to_string()),
+            None => JsonValue::Null,
+        },
+    );
+    #[allow(clippy::cast_precision_loss)]
+    map.insert(
+        "nanos".into(),
+        JsonValue::Number(duration.as_nanos() as f64),
+    );
+    #[allow(clippy::cast_precision_loss)]
+    map.insert("samples".into(), JsonValue::Number(samples as f64));
 
-    let Ok(part_submit) = args[part_index].parse::<u8>() else {
-        eprintln!("Unexpected command-line input. Format: cargo solve 1 --submit 1");
-        process::exit(1);
-    };
+    // serializing a map of strings and numbers can not fail.
+    println!("{}", JsonValue::Object(map).stringify().unwrap());
+}
 
-    if part_submit != part {
+/// Try to submit one part of the solution if:
+///  1. `--submit` was passed for this part.
+///  2. aoc-cli is installed.
+fn submit_result<T: Display>(
+    result: T,
+    day: Day,
+    part: u8,
+    args: &SolutionArgs,
+) -> Option<Result<Output, aoc_cli::AocCommandError>> {
+    if args.submit != Some(part) {
         return None;
     }
 
-    if aoc_cli::check().is_err() {
+    if let Err(e) = aoc_cli::check() {
         eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
-        process::exit(1);
+        return Some(Err(e));
     }
 
     println!("Submitting result via aoc-cli...");
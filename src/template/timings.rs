@@ -1,9 +1,7 @@
 use std::{collections::HashMap, fs, io::Error, str::FromStr};
 use tinyjson::JsonValue;
 
-use crate::template::Day;
-
-static TIMINGS_FILE_PATH: &str = "./data/timings.json";
+use crate::template::{Config, Day};
 
 /// Represents benchmark times for a single day.
 #[derive(Clone, Debug)]
@@ -25,13 +23,13 @@ impl Timings {
     /// Dehydrate timings to a JSON file.
     pub fn store_file(&self) -> Result<(), Error> {
         let json = JsonValue::from(self.clone());
-        let mut file = fs::File::create(TIMINGS_FILE_PATH)?;
+        let mut file = fs::File::create(&Config::get().paths.timings)?;
         json.format_to(&mut file)
     }
 
     /// Rehydrate timings from a JSON file. If not present, returns empty timings.
     pub fn read_from_file() -> Self {
-        fs::read_to_string(TIMINGS_FILE_PATH)
+        fs::read_to_string(&Config::get().paths.timings)
             .map_err(|x| x.to_string())
             .and_then(Timings::try_from)
             .unwrap_or_default()
@@ -285,7 +283,7 @@ mod tests {
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), true);
+            assert_eq!(timings.is_day_complete(day!(1)), true);
         }
 
         #[test]
@@ -299,7 +297,7 @@ mod tests {
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(day!(1)), false);
         }
 
         #[test]
@@ -313,7 +311,7 @@ mod tests {
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(day!(1)), false);
         }
     }
 
@@ -0,0 +1,373 @@
+//! Image files of canvases, for looking at a grid in an image viewer instead of a terminal.
+//!
+//! Each cell is drawn as a square of pixels in the colour of its [`Class`]. Glyphs aren't drawn. Solutions write images
+//! with [`save`] and animations with a [`Recording`], which end up in `data/viz/DD/`. Like frames, nothing is drawn or
+//! written unless the solution runs with `--viz`.
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::template::{Config, Day};
+
+use super::{Canvas, Class, OPTIONS};
+
+/// The side of the square each cell is drawn as, in pixels.
+pub const SCALE: usize = 4;
+
+/// How long each frame of a [`Recording`] is shown, unless `--viz-delay` is given.
+const DEFAULT_DELAY: Duration = Duration::from_millis(100);
+
+/// The colour of each [`Class`], in the order of its variants.
+const PALETTE: [[u8; 3]; 6] = [
+    [24, 24, 24],
+    [200, 200, 200],
+    [230, 180, 40],
+    [60, 200, 80],
+    [60, 170, 220],
+    [220, 50, 50],
+];
+
+impl Class {
+    fn palette_index(self) -> u8 {
+        self as u8
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Ppm,
+    Png,
+    /// An animated GIF if there's more than one frame.
+    Gif,
+}
+
+impl Format {
+    /// The format for a file extension, e.g. `png`.
+    pub fn from_extension(path: &Path) -> Option<Format> {
+        match path.extension()?.to_str()? {
+            "ppm" => Some(Format::Ppm),
+            "png" => Some(Format::Png),
+            "gif" => Some(Format::Gif),
+            _ => None,
+        }
+    }
+}
+
+/// A canvas as pixels, each an index into the colours of the classes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl Image {
+    /// Draw each cell of a canvas as a `scale` by `scale` square. A canvas without cells gives an empty image.
+    pub fn from_canvas(canvas: &Canvas, scale: usize) -> Image {
+        if canvas.cells.is_empty() {
+            return Image {
+                width: 0,
+                height: 0,
+                pixels: Vec::new(),
+            };
+        }
+        let width = canvas.size.x() * scale;
+        let mut pixels = Vec::with_capacity(width * canvas.size.y() * scale);
+        for row in canvas.cells.chunks(*canvas.size.x()) {
+            let start = pixels.len();
+            for cell in row {
+                let colour = cell.class.palette_index();
+                pixels.extend(std::iter::repeat_n(colour, scale));
+            }
+            for _ in 1..scale {
+                pixels.extend_from_within(start..start + width);
+            }
+        }
+        Image {
+            width,
+            height: canvas.size.y() * scale,
+            pixels,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The colour of a pixel, with `(0, 0)` at the top left.
+    pub fn pixel(&self, x: usize, y: usize) -> [u8; 3] {
+        PALETTE[self.pixels[y * self.width + x] as usize]
+    }
+
+    /// Write the image as a binary PPM, which almost anything can read.
+    pub fn write_ppm(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+        let rgb = self
+            .pixels
+            .iter()
+            .flat_map(|&i| PALETTE[i as usize])
+            .collect::<Vec<_>>();
+        out.write_all(&rgb)
+    }
+
+    pub fn write_png(&self, out: &mut impl Write) -> io::Result<()> {
+        let (width, height) = (dimension(self.width)?, dimension(self.height)?);
+        let mut encoder = png::Encoder::new(out, width, height);
+        encoder.set_color(png::ColorType::Indexed);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_palette(PALETTE.concat());
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels)?;
+        Ok(writer.finish()?)
+    }
+}
+
+fn dimension<T: TryFrom<usize>>(len: usize) -> io::Result<T> {
+    T::try_from(len).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{len} pixels is too large for an image"),
+        )
+    })
+}
+
+/// Write frames as a GIF that loops forever, showing each frame for `delay`. All frames must be the same size.
+pub fn write_gif(frames: &[Image], delay: Duration, out: &mut impl Write) -> io::Result<()> {
+    let Some(first) = frames.first() else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "a GIF needs at least one frame",
+        ));
+    };
+    if frames
+        .iter()
+        .any(|image| (image.width, image.height) != (first.width, first.height))
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "all frames of a GIF must be the same size",
+        ));
+    }
+    let (width, height) = (dimension(first.width)?, dimension(first.height)?);
+    // GIF delays are in hundredths of a second.
+    let delay = (delay.as_millis() / 10).try_into().unwrap_or(u16::MAX);
+
+    let mut encoder =
+        gif::Encoder::new(out, width, height, &PALETTE.concat()).map_err(io::Error::other)?;
+    encoder
+        .set_repeat(gif::Repeat::Infinite)
+        .map_err(io::Error::other)?;
+    for image in frames {
+        let mut frame =
+            gif::Frame::from_indexed_pixels(width, height, image.pixels.as_slice(), None);
+        frame.delay = delay;
+        encoder.write_frame(&frame).map_err(io::Error::other)?;
+    }
+    Ok(())
+}
+
+/// Write frames to a file in the format given by its extension. Only GIFs can have more than one frame.
+pub fn write_file(path: &Path, frames: &[Image], delay: Duration) -> io::Result<()> {
+    let format = Format::from_extension(path).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "expected a .ppm, .png or .gif file",
+        )
+    })?;
+    if frames.is_empty() || (frames.len() > 1 && format != Format::Gif) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "expected one frame, or any number for a GIF",
+        ));
+    }
+
+    let mut out = create(path)?;
+    match format {
+        Format::Ppm => frames[0].write_ppm(&mut out)?,
+        Format::Png => frames[0].write_png(&mut out)?,
+        Format::Gif => write_gif(frames, delay, &mut out)?,
+    }
+    out.flush()
+}
+
+fn create(path: &Path) -> io::Result<BufWriter<File>> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    Ok(BufWriter::new(File::create(path)?))
+}
+
+/// Where a solution's image called `name` is written, i.e. `data/viz/DD/<name>`.
+pub fn path(day: Day, name: &str) -> PathBuf {
+    Config::get()
+        .data_folder("viz")
+        .join(day.to_string())
+        .join(name)
+}
+
+/// Write a canvas to `data/viz/DD/<name>`, where the extension of `name` picks the format. `draw` is only called if
+/// visualisations are enabled.
+pub fn save(day: Day, name: &str, draw: impl FnOnce() -> Canvas) {
+    if OPTIONS.get().is_none() {
+        return;
+    }
+    let path = path(day, name);
+    let image = Image::from_canvas(&draw(), SCALE);
+    report(&path, write_file(&path, &[image], DEFAULT_DELAY));
+}
+
+fn report(path: &Path, result: io::Result<()>) {
+    match result {
+        Ok(()) => eprintln!("Wrote {}", path.display()),
+        Err(e) => eprintln!("Could not write {}: {e}", path.display()),
+    }
+}
+
+/// Collects frames of a simulation and writes them as an animated GIF to `data/viz/DD/<name>` when finished or
+/// dropped. Each frame is shown for the `--viz-delay`, or 100ms.
+pub struct Recording {
+    path: PathBuf,
+    frames: Vec<Image>,
+}
+
+impl Recording {
+    pub fn new(day: Day, name: &str) -> Recording {
+        Recording {
+            path: path(day, name),
+            frames: Vec::new(),
+        }
+    }
+
+    /// Add a frame. `draw` is only called if visualisations are enabled.
+    pub fn frame(&mut self, draw: impl FnOnce() -> Canvas) {
+        if OPTIONS.get().is_some() {
+            self.frames.push(Image::from_canvas(&draw(), SCALE));
+        }
+    }
+
+    /// Write the GIF now rather than when the recording is dropped.
+    pub fn finish(self) {}
+}
+
+impl Drop for Recording {
+    fn drop(&mut self) {
+        let Some(options) = OPTIONS.get() else {
+            return;
+        };
+        if self.frames.is_empty() {
+            return;
+        }
+        let delay = options.delay.unwrap_or(DEFAULT_DELAY);
+        let frames = std::mem::take(&mut self.frames);
+        report(&self.path, write_file(&self.path, &frames, delay));
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::Duration;
+
+    use numeric::compound::vector::Vec2;
+
+    use super::{write_gif, Image, PALETTE};
+    use crate::viz::{Canvas, Cell, Class};
+
+    fn canvas() -> Canvas {
+        let mut canvas = Canvas::new(Vec2::from_xy(3, 2));
+        canvas.set(Vec2::from_xy(1u32, 0), Cell::new('#', Class::Wall));
+        canvas.set(Vec2::from_xy(2u32, 1), Cell::new('@', Class::Agent));
+        canvas
+    }
+
+    #[test]
+    fn scales_cells_to_squares() {
+        let image = Image::from_canvas(&canvas(), 2);
+        assert_eq!((image.width(), image.height()), (6, 4));
+        for (x, y) in [(2, 0), (3, 0), (2, 1), (3, 1)] {
+            assert_eq!(image.pixel(x, y), PALETTE[Class::Wall as usize]);
+        }
+        assert_eq!(image.pixel(5, 3), PALETTE[Class::Agent as usize]);
+        assert_eq!(image.pixel(1, 1), PALETTE[Class::Empty as usize]);
+    }
+
+    #[test]
+    fn draws_empty_canvases_as_empty_images() {
+        for size in [Vec2::from_xy(0, 2), Vec2::from_xy(2, 0)] {
+            let image = Image::from_canvas(&Canvas::new(size), 2);
+            assert_eq!((image.width(), image.height()), (0, 0));
+        }
+    }
+
+    #[test]
+    fn writes_ppm() {
+        let mut out = Vec::new();
+        Image::from_canvas(&canvas(), 1)
+            .write_ppm(&mut out)
+            .unwrap();
+        let (header, rgb) = out.split_at(11);
+        assert_eq!(header, b"P6\n3 2\n255\n");
+        assert_eq!(rgb.len(), 3 * 2 * 3);
+        assert_eq!(rgb[3..6], PALETTE[Class::Wall as usize]);
+    }
+
+    #[test]
+    fn png_round_trips() {
+        let image = Image::from_canvas(&canvas(), 3);
+        let mut out = Vec::new();
+        image.write_png(&mut out).unwrap();
+
+        let decoder = png::Decoder::new(out.as_slice());
+        let mut reader = decoder.read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        assert_eq!((info.width, info.height), (9, 6));
+        assert_eq!(info.color_type, png::ColorType::Indexed);
+        assert_eq!(pixels, image.pixels);
+    }
+
+    #[test]
+    fn gif_round_trips() {
+        let first = Image::from_canvas(&canvas(), 2);
+        let mut moved = canvas();
+        moved.set(Vec2::from_xy(0u32, 1), Cell::new('@', Class::Agent));
+        let second = Image::from_canvas(&moved, 2);
+
+        let mut out = Vec::new();
+        write_gif(
+            &[first.clone(), second.clone()],
+            Duration::from_millis(250),
+            &mut out,
+        )
+        .unwrap();
+
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::Indexed);
+        let mut decoder = options.read_info(out.as_slice()).unwrap();
+        let mut frames = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            assert_eq!(frame.delay, 25);
+            frames.push(frame.buffer.to_vec());
+        }
+        assert_eq!(frames, [first.pixels, second.pixels]);
+    }
+
+    #[test]
+    fn rejects_gif_frames_of_different_sizes() {
+        let frames = [
+            Image::from_canvas(&canvas(), 2),
+            Image::from_canvas(&canvas(), 3),
+        ];
+        let mut out = Vec::new();
+        let err = write_gif(&frames, Duration::from_millis(100), &mut out).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(out.is_empty());
+    }
+}
@@ -0,0 +1,302 @@
+//! Terminal visualisations of grids and simulations.
+//!
+//! Solutions draw a [`Canvas`] and pass it to [`frame`]. Frames are only built and shown when the solution runs with
+//! `--viz`, so the calls can stay in a solution. They are written to stderr, so the results on stdout (including
+//! `--format json`) are unaffected. With `--viz-delay <ms>`, each frame replaces the previous one and stays for the
+//! delay, which animates a simulation.
+//!
+//! Canvases can also be written to image files with [`save`] and [`Recording`], see [`export`].
+
+use std::fmt::{self, Display, Write as _};
+use std::io::{self, IsTerminal, Write as _};
+use std::sync::OnceLock;
+use std::thread;
+use std::time::Duration;
+
+use numeric::compound::vector::Vec2;
+
+use crate::template::ANSI_RESET;
+use crate::Cardinal;
+
+pub mod export;
+
+pub use export::{save, Recording};
+
+static OPTIONS: OnceLock<Options> = OnceLock::new();
+
+const ANSI_CLEAR_SCREEN: &str = "\x1b[H\x1b[2J";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Options {
+    /// Animate: draw each frame over the previous one and wait this long after it.
+    pub delay: Option<Duration>,
+}
+
+/// Show frames for the rest of the process. The runner calls this for `--viz`.
+pub fn enable(options: Options) {
+    let _ = OPTIONS.set(options);
+}
+
+pub fn enabled() -> bool {
+    OPTIONS.get().is_some()
+}
+
+/// Show a frame with a title, e.g. the step of a simulation. `draw` is only called if visualisations are enabled.
+pub fn frame(title: impl Display, draw: impl FnOnce() -> Canvas) {
+    let Some(options) = OPTIONS.get() else {
+        return;
+    };
+    let canvas = draw();
+
+    let mut out = String::new();
+    if options.delay.is_some() {
+        out.push_str(ANSI_CLEAR_SCREEN);
+    }
+    let _ = writeln!(out, "{title}");
+    out.push_str(&canvas.render(io::stderr().is_terminal()));
+    out.push('\n');
+    let _ = io::stderr().lock().write_all(out.as_bytes());
+
+    if let Some(delay) = options.delay {
+        thread::sleep(delay);
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// What a cell shows, which picks its colour.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Class {
+    Empty,
+    Wall,
+    /// Things that are part of the puzzle, e.g. boxes, antennas or files.
+    Object,
+    /// Whatever moves, e.g. a guard or a robot.
+    Agent,
+    Path,
+    /// Cells the solution is looking for, e.g. antinodes.
+    Highlight,
+}
+
+impl Class {
+    fn ansi(self) -> &'static str {
+        match self {
+            Class::Empty => "\x1b[2m",
+            Class::Wall => "\x1b[1m",
+            Class::Object => "\x1b[33m",
+            Class::Agent => "\x1b[1;32m",
+            Class::Path => "\x1b[36m",
+            Class::Highlight => "\x1b[1;31m",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cell {
+    pub glyph: char,
+    pub class: Class,
+}
+
+impl Cell {
+    pub const EMPTY: Cell = Cell::new('.', Class::Empty);
+
+    pub const fn new(glyph: char, class: Class) -> Cell {
+        Cell { glyph, class }
+    }
+}
+
+/// A grid of cells to show in a frame.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Canvas {
+    size: Vec2<usize>,
+    /// Row by row, starting with the top row.
+    cells: Vec<Cell>,
+    /// Whether `y = 0` is the bottom row.
+    y_up: bool,
+}
+
+impl Canvas {
+    /// An empty canvas where `y = 0` is the top row, like the first line of an input.
+    pub fn new(size: Vec2<usize>) -> Canvas {
+        Canvas {
+            size,
+            cells: vec![Cell::EMPTY; size.x() * size.y()],
+            y_up: false,
+        }
+    }
+
+    /// An empty canvas where `y = 0` is the bottom row, for positions in math coordinates (see [`crate::VecExt`]).
+    pub fn y_up(size: Vec2<usize>) -> Canvas {
+        Canvas {
+            y_up: true,
+            ..Canvas::new(size)
+        }
+    }
+
+    /// A canvas where `y = 0` is the top row, with the cells returned by `cell`.
+    pub fn from_fn(size: Vec2<usize>, mut cell: impl FnMut(Vec2<usize>) -> Cell) -> Canvas {
+        let cells = (0..*size.y())
+            .flat_map(|y| (0..*size.x()).map(move |x| Vec2::from_xy(x, y)))
+            .map(&mut cell)
+            .collect();
+        Canvas {
+            size,
+            cells,
+            y_up: false,
+        }
+    }
+
+    pub fn size(&self) -> Vec2<usize> {
+        self.size
+    }
+
+    fn index<I: Copy + TryInto<usize>>(&self, pos: Vec2<I>) -> Option<usize> {
+        let x: usize = (*pos.x()).try_into().ok()?;
+        let y: usize = (*pos.y()).try_into().ok()?;
+        if x >= *self.size.x() || y >= *self.size.y() {
+            return None;
+        }
+        let row = if self.y_up { self.size.y() - 1 - y } else { y };
+        Some(row * self.size.x() + x)
+    }
+
+    pub fn get<I: Copy + TryInto<usize>>(&self, pos: Vec2<I>) -> Option<Cell> {
+        Some(self.cells[self.index(pos)?])
+    }
+
+    /// Set a cell. Positions outside the canvas are ignored.
+    pub fn set<I: Copy + TryInto<usize>>(&mut self, pos: Vec2<I>, cell: Cell) {
+        if let Some(i) = self.index(pos) {
+            self.cells[i] = cell;
+        }
+    }
+
+    /// Draw a path through a cell with a box-drawing character, given the directions the cell was walked in.
+    /// Cells that weren't walked are left as they are.
+    pub fn path<I: Copy + TryInto<usize>>(
+        &mut self,
+        pos: Vec2<I>,
+        directions: impl IntoIterator<Item = Cardinal>,
+    ) {
+        let [mut up, mut down, mut left, mut right] = [false; 4];
+        for dir in directions {
+            match dir {
+                Cardinal::Up => up = true,
+                Cardinal::Down => down = true,
+                Cardinal::Left => left = true,
+                Cardinal::Right => right = true,
+            }
+        }
+        if let Some(glyph) = path_glyph(up, down, left, right) {
+            self.set(pos, Cell::new(glyph, Class::Path));
+        }
+    }
+
+    /// The canvas as lines of text, with ANSI colours if `colour` is set. A canvas without cells renders as nothing.
+    pub fn render(&self, colour: bool) -> String {
+        let mut out = String::new();
+        if self.cells.is_empty() {
+            return out;
+        }
+        for row in self.cells.chunks(*self.size.x()) {
+            let mut class = None;
+            for cell in row {
+                if colour && class != Some(cell.class) {
+                    out.push_str(ANSI_RESET);
+                    out.push_str(cell.class.ansi());
+                    class = Some(cell.class);
+                }
+                out.push(cell.glyph);
+            }
+            if colour {
+                out.push_str(ANSI_RESET);
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+impl Display for Canvas {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(false))
+    }
+}
+
+/// The character for a cell walked in the given directions. A turn is drawn as the corner it turns from, e.g. `└` for
+/// walking up and then turning left.
+fn path_glyph(up: bool, down: bool, left: bool, right: bool) -> Option<char> {
+    let glyph = match (up, down, left, right) {
+        (true, true, true, true) => '┼',
+        (true, true, true, false) => '├',
+        (true, true, false, true) => '┥',
+        (true, true, false, false) => '│',
+        (true, false, true, true) => '┬',
+        (true, false, true, false) => '└',
+        (true, false, false, true) => '┌',
+        (true, false, false, false) => '│',
+        (false, true, true, true) => '┴',
+        (false, true, true, false) => '┘',
+        (false, true, false, true) => '┐',
+        (false, true, false, false) => '│',
+        (false, false, true, true) => '─',
+        (false, false, true, false) => '─',
+        (false, false, false, true) => '─',
+        (false, false, false, false) => return None,
+    };
+    Some(glyph)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use numeric::compound::vector::Vec2;
+
+    use super::{Canvas, Cell, Class};
+    use crate::Cardinal;
+
+    #[test]
+    fn renders_in_either_orientation() {
+        let mut canvas = Canvas::new(Vec2::from_xy(3, 2));
+        canvas.set(Vec2::from_xy(0u32, 0), Cell::new('#', Class::Wall));
+        canvas.set(Vec2::from_xy(-1i32, 0), Cell::new('#', Class::Wall));
+        canvas.set(Vec2::from_xy(3u32, 0), Cell::new('#', Class::Wall));
+        assert_eq!(canvas.to_string(), "#..\n...\n");
+
+        let mut canvas = Canvas::y_up(Vec2::from_xy(3, 2));
+        canvas.set(Vec2::from_xy(0u32, 0), Cell::new('#', Class::Wall));
+        assert_eq!(canvas.to_string(), "...\n#..\n");
+        assert_eq!(
+            canvas.get(Vec2::from_xy(0u32, 0)),
+            Some(Cell::new('#', Class::Wall))
+        );
+    }
+
+    #[test]
+    fn renders_colours_by_class() {
+        let canvas = Canvas::from_fn(Vec2::from_xy(3, 1), |pos| match pos.x() {
+            0 => Cell::new('@', Class::Agent),
+            _ => Cell::EMPTY,
+        });
+        assert_eq!(
+            canvas.render(true),
+            "\x1b[0m\x1b[1;32m@\x1b[0m\x1b[2m..\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn draws_paths_with_box_drawing_characters() {
+        let mut canvas = Canvas::y_up(Vec2::from_xy(3, 1));
+        canvas.path(Vec2::from_xy(0u32, 0), [Cardinal::Up, Cardinal::Down]);
+        canvas.path(Vec2::from_xy(1u32, 0), [Cardinal::Up, Cardinal::Right]);
+        canvas.path(Vec2::from_xy(2u32, 0), []);
+        assert_eq!(canvas.to_string(), "│┌.\n");
+    }
+
+    #[test]
+    fn renders_empty_canvases() {
+        assert_eq!(Canvas::new(Vec2::from_xy(0, 2)).render(true), "");
+        assert_eq!(Canvas::new(Vec2::from_xy(2, 0)).render(true), "");
+    }
+}